@@ -10,7 +10,6 @@ use tempfile::NamedTempFile;
 fn bench_line_classification(c: &mut Criterion) {
     let rust_lang = LANGUAGES.iter().find(|l| l.name == "Rust").unwrap();
     let classifier = LineClassifier::new(rust_lang);
-    let mut state = ClassifierState::new();
 
     let mut group = c.benchmark_group("line_classification");
 
@@ -157,7 +156,9 @@ fn bench_stats_aggregation(c: &mut Criterion) {
                     FileStats {
                         blank: 10,
                         comment: 20,
+                        doc: 0,
                         code: 70,
+                        mixed: 0,
                     },
                 );
             }
@@ -181,7 +182,9 @@ fn bench_stats_aggregation(c: &mut Criterion) {
                 FileStats {
                     blank: 10,
                     comment: 20,
+                    doc: 0,
                     code: 70,
+                    mixed: 0,
                 },
             );
         }
@@ -198,7 +201,9 @@ fn bench_stats_aggregation(c: &mut Criterion) {
                 FileStats {
                     blank: 10,
                     comment: 20,
+                    doc: 0,
                     code: 70,
+                    mixed: 0,
                 },
             );
         }
@@ -216,7 +221,7 @@ fn bench_file_processing(c: &mut Criterion) {
     let mut temp_file = NamedTempFile::new().unwrap();
     for i in 0..100 {
         if i % 10 == 0 {
-            writeln!(temp_file, "").unwrap();
+            writeln!(temp_file).unwrap();
         } else if i % 5 == 0 {
             writeln!(temp_file, "// Comment {}", i).unwrap();
         } else {
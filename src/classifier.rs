@@ -1,29 +1,45 @@
 //! Line classification engine.
 //!
 //! This module implements the logic for classifying lines as
-//! blank, comment, or code based on language syntax rules.
+//! blank, comment, documentation, code, or mixed based on language syntax rules.
 
-use crate::language::{CommentPair, LanguageInfo};
+use crate::language::{CommentPair, LanguageInfo, StringDelimiter};
 use crate::stats::FileStats;
 
 /// Type of a line in source code.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LineType {
     /// Line contains only whitespace.
     Blank,
-    /// Line contains only comments.
+    /// Line contains only ordinary comments.
     Comment,
-    /// Line contains code (may also contain comments).
+    /// Line contains documentation comments (may also contain ordinary comments).
+    Doc,
+    /// Line contains only code.
     Code,
+    /// Line contains both code and comments (ordinary or documentation).
+    Mixed,
 }
 
-/// State tracker for multi-line comment processing.
+/// State tracker for multi-line comment and string processing.
 #[derive(Debug, Clone)]
 pub struct ClassifierState {
     /// Whether we're currently inside a multi-line comment.
     in_multi_line_comment: bool,
     /// The delimiter pair we're currently inside (if any).
     current_delimiter: Option<CommentPair>,
+    /// Whether the multi-line comment we're inside is documentation.
+    in_doc_comment: bool,
+    /// How many comments deep we are, for languages where comments nest.
+    comment_depth: usize,
+    /// The multi-line string we're currently inside (if any).
+    current_string: Option<StringDelimiter>,
+    /// Whether the multi-line string we're inside is a docstring.
+    in_docstring: bool,
+    /// How many brackets are open, for languages with docstrings.
+    open_brackets: usize,
+    /// Whether the previous line continues on this one, for languages with docstrings.
+    continued_line: bool,
 }
 
 impl ClassifierState {
@@ -32,6 +48,12 @@ impl ClassifierState {
         Self {
             in_multi_line_comment: false,
             current_delimiter: None,
+            in_doc_comment: false,
+            comment_depth: 0,
+            current_string: None,
+            in_docstring: false,
+            open_brackets: 0,
+            continued_line: false,
         }
     }
 
@@ -39,6 +61,12 @@ impl ClassifierState {
     pub fn reset(&mut self) {
         self.in_multi_line_comment = false;
         self.current_delimiter = None;
+        self.in_doc_comment = false;
+        self.comment_depth = 0;
+        self.current_string = None;
+        self.in_docstring = false;
+        self.open_brackets = 0;
+        self.continued_line = false;
     }
 }
 
@@ -48,6 +76,16 @@ impl Default for ClassifierState {
     }
 }
 
+/// A token that starts a comment or a string.
+enum Token<'a> {
+    /// A single-line comment marker: the rest of the line is a comment.
+    SingleComment { doc: bool },
+    /// The start of a multi-line comment.
+    MultiComment { pair: &'a CommentPair, doc: bool },
+    /// The start of a string literal.
+    String(&'a StringDelimiter),
+}
+
 /// Line classifier that determines line types based on language rules.
 pub struct LineClassifier<'a> {
     language: &'a LanguageInfo,
@@ -69,133 +107,321 @@ impl<'a> LineClassifier<'a> {
         Self::trim_line(line).is_empty()
     }
 
-    /// Check if a line starts with a single-line comment.
-    fn starts_with_single_comment(&self, line: &str) -> bool {
-        let trimmed = Self::trim_line(line);
-        for comment in self.language.single_line_comments {
-            if trimmed.starts_with(comment) {
-                return true;
+    /// Find the next token that starts a comment or a string in a line.
+    ///
+    /// The earliest token wins; among tokens at the same position, the
+    /// longest one wins.
+    fn next_token(&self, line: &str) -> Option<(usize, Token<'a>)> {
+        let language = self.language;
+        let single = |doc| move |marker: &&'a str| (*marker, Token::SingleComment { doc });
+        let multi =
+            |doc| move |pair: &'a CommentPair| (pair.start, Token::MultiComment { pair, doc });
+        let comments = language
+            .single_line_comments
+            .iter()
+            .map(single(false))
+            .chain(language.doc_comments.iter().map(single(true)))
+            .chain(language.multi_line_comments.iter().map(multi(false)))
+            .chain(language.multi_line_doc_comments.iter().map(multi(true)));
+        let strings = language
+            .strings
+            .iter()
+            .map(|string| (string.start, Token::String(string)));
+
+        let mut best: Option<(usize, &str, Token<'a>)> = None;
+        for (marker, token) in comments.chain(strings) {
+            let found = match token {
+                Token::String(string) if string.char_literal => line
+                    .match_indices(marker)
+                    .map(|(pos, _)| pos)
+                    .find(|&pos| Self::is_char_literal(&line[pos + marker.len()..], string)),
+                _ => line.find(marker),
+            };
+            let Some(pos) = found else {
+                continue;
+            };
+            let doc = matches!(
+                token,
+                Token::SingleComment { doc: true } | Token::MultiComment { doc: true, .. }
+            );
+            if doc && !self.is_doc_marker(line, pos, marker) {
+                continue;
             }
+            let better = match &best {
+                None => true,
+                Some((best_pos, best_marker, _)) => {
+                    pos < *best_pos || (pos == *best_pos && marker.len() > best_marker.len())
+                }
+            };
+            if better {
+                best = Some((pos, marker, token));
+            }
+        }
+
+        best.map(|(pos, _, token)| (pos, token))
+    }
+
+    /// Check if a documentation comment marker found at `pos` starts documentation.
+    ///
+    /// Repeating the marker's last character (`////`, `/***`) or closing the
+    /// comment right away (`/**/`) makes an ordinary comment instead.
+    fn is_doc_marker(&self, line: &str, pos: usize, marker: &str) -> bool {
+        let after = &line[pos + marker.len()..];
+        if marker.chars().last().is_some_and(|c| after.starts_with(c)) {
+            return false;
         }
-        false
+        !self.language.multi_line_comments.iter().any(|pair| {
+            line[pos..]
+                .strip_prefix(pair.start)
+                .is_some_and(|rest| rest.starts_with(pair.end))
+        })
+    }
+
+    /// Check if the text following a char literal's start delimiter closes it
+    /// after a single character or escape sequence (`'x'`, `'\n'`, `'\u{..}'`).
+    ///
+    /// Otherwise the start delimiter is not a string, like the `'` of a lifetime.
+    fn is_char_literal(after: &str, string: &StringDelimiter) -> bool {
+        let mut chars = after.chars();
+        let rest = match chars.next() {
+            None => return false,
+            Some(c) if Some(c) == string.escape => match chars.next() {
+                Some('u') if chars.as_str().starts_with('{') => {
+                    let rest = chars.as_str();
+                    match rest.find('}') {
+                        Some(end) => &rest[end + 1..],
+                        None => return false,
+                    }
+                }
+                Some('x') => chars.as_str().get(2..).unwrap_or(""),
+                Some(_) => chars.as_str(),
+                None => return false,
+            },
+            Some(_) => chars.as_str(),
+        };
+        rest.starts_with(string.end)
+    }
+
+    /// Get the delimiters that nest inside a documentation comment.
+    ///
+    /// This is the ordinary comment pair with the same end, so `/*` nests
+    /// inside `/** */`; the documentation pair itself if there's none.
+    fn nesting_pair(&self, doc_pair: &'a CommentPair) -> &'a CommentPair {
+        self.language
+            .multi_line_comments
+            .iter()
+            .find(|pair| pair.end == doc_pair.end)
+            .unwrap_or(doc_pair)
     }
 
-    /// Find the position of a multi-line comment start delimiter in a line.
-    fn contains_multi_line_start(&self, line: &str) -> Option<(usize, &CommentPair)> {
-        for pair in self.language.multi_line_comments {
-            if let Some(pos) = line.find(pair.start) {
-                return Some((pos, pair));
+    /// Track the brackets opened and closed by a piece of code.
+    ///
+    /// Only languages with docstrings need this, to tell a string that's a
+    /// statement of its own from one inside an expression.
+    fn track_brackets(&self, code: &str, state: &mut ClassifierState) {
+        if !self.language.docstrings {
+            return;
+        }
+        for c in code.chars() {
+            match c {
+                '(' | '[' | '{' => state.open_brackets += 1,
+                ')' | ']' | '}' => state.open_brackets = state.open_brackets.saturating_sub(1),
+                _ => {}
             }
         }
-        None
     }
 
-    /// Find the position of a multi-line comment end delimiter in a line.
-    fn contains_multi_line_end(&self, line: &str, delimiter: &CommentPair) -> Option<usize> {
-        line.find(delimiter.end)
-            .map(|pos| pos + delimiter.end.len())
+    /// Find the end of a string in the text following its start delimiter.
+    ///
+    /// Returns the position right after the end delimiter, or None if the
+    /// string doesn't end in the text.
+    fn find_string_end(text: &str, string: &StringDelimiter) -> Option<usize> {
+        let mut chars = text.char_indices();
+        while let Some((pos, c)) = chars.next() {
+            if text[pos..].starts_with(string.end) {
+                return Some(pos + string.end.len());
+            }
+            if Some(c) == string.escape {
+                chars.next();
+            }
+        }
+        None
     }
 
     /// Classify a single line of code.
     ///
-    /// This method updates the state and returns the line type.
+    /// The line is scanned from left to right for comments and strings, so
+    /// comment markers inside strings are ignored. This method updates the
+    /// state and returns the line type.
     pub fn classify_line(&self, line: &str, state: &mut ClassifierState) -> LineType {
         // Check for blank line first
         if Self::is_blank(line) {
             return LineType::Blank;
         }
 
-        let trimmed = Self::trim_line(line);
-
         // Handle shebang lines as code
-        if trimmed.starts_with("#!") {
+        if state.current_delimiter.is_none() && Self::trim_line(line).starts_with("#!") {
             return LineType::Code;
         }
 
-        // If we're already in a multi-line comment
-        if state.in_multi_line_comment {
-            if let Some(ref delimiter) = state.current_delimiter {
-                // Look for the end delimiter
-                if let Some(end_pos) = self.contains_multi_line_end(line, delimiter) {
-                    // Comment ends on this line
-                    state.in_multi_line_comment = false;
-                    state.current_delimiter = None;
-
-                    // Check if there's code after the comment end
-                    let after_comment = &line[end_pos..];
-                    if !Self::is_blank(after_comment) {
-                        // There's code after the comment
-                        return LineType::Code;
-                    } else {
-                        return LineType::Comment;
+        let mut has_code = false;
+        let mut has_doc = false;
+        let mut has_comment = false;
+        let mut ends_in_comment = false;
+        let mut pos = 0;
+        while pos < line.len() {
+            let rest = &line[pos..];
+
+            // Inside a multi-line comment: skip to its end
+            if let Some(pair) = state.current_delimiter.clone() {
+                has_comment = true;
+                has_doc |= state.in_doc_comment;
+                let end = rest.find(pair.end).map(|end_pos| (end_pos, pair.end));
+                let nested = rest
+                    .find(pair.start)
+                    .filter(|_| self.language.nested_comments)
+                    .map(|start_pos| (start_pos, pair.start));
+
+                match (end, nested) {
+                    // A nested comment opens before the current one ends
+                    (_, Some((start_pos, start)))
+                        if end.is_none_or(|(end_pos, _)| start_pos < end_pos) =>
+                    {
+                        state.comment_depth += 1;
+                        pos += start_pos + start.len();
                     }
-                } else {
-                    // Still in comment, no end delimiter on this line
-                    return LineType::Comment;
+                    (Some((end_pos, end)), _) => {
+                        state.comment_depth -= 1;
+                        if state.comment_depth == 0 {
+                            state.in_multi_line_comment = false;
+                            state.current_delimiter = None;
+                            state.in_doc_comment = false;
+                        }
+                        pos += end_pos + end.len();
+                    }
+                    _ => break,
                 }
+                continue;
             }
-        }
-
-        // Not in multi-line comment
-        // Check for single-line comment
-        if self.starts_with_single_comment(trimmed) {
-            return LineType::Comment;
-        }
-
-        // Check for multi-line comment start
-        if let Some((start_pos, pair)) = self.contains_multi_line_start(line) {
-            // Check if it also ends on the same line
-            let after_start = &line[start_pos + pair.start.len()..];
-            if let Some(end_pos_relative) = after_start.find(pair.end) {
-                // Multi-line comment starts and ends on same line
-                let end_pos = start_pos + pair.start.len() + end_pos_relative + pair.end.len();
-
-                // Check if there's code before or after
-                let before_comment = &line[..start_pos];
-                let after_comment = &line[end_pos..];
 
-                if !Self::is_blank(before_comment) || !Self::is_blank(after_comment) {
-                    return LineType::Code;
+            // Inside a multi-line string: skip to its end, it's all code
+            // unless the string is a docstring
+            if let Some(string) = &state.current_string {
+                if state.in_docstring {
+                    has_doc = true;
                 } else {
-                    return LineType::Comment;
+                    has_code = true;
                 }
-            } else {
-                // Multi-line comment starts but doesn't end
-                state.in_multi_line_comment = true;
-                state.current_delimiter = Some(pair.clone());
-
-                // Check if there's code before the comment start
-                let before_comment = &line[..start_pos];
-                if !Self::is_blank(before_comment) {
-                    return LineType::Code;
-                } else {
-                    return LineType::Comment;
+                match Self::find_string_end(rest, string) {
+                    Some(end_pos) => {
+                        state.current_string = None;
+                        state.in_docstring = false;
+                        pos += end_pos;
+                        continue;
+                    }
+                    None => break,
+                }
+            }
+
+            let Some((token_pos, token)) = self.next_token(rest) else {
+                has_code |= !Self::is_blank(rest);
+                self.track_brackets(rest, state);
+                break;
+            };
+            has_code |= !Self::is_blank(&rest[..token_pos]);
+            self.track_brackets(&rest[..token_pos], state);
+
+            match token {
+                // The rest of the line is a comment
+                Token::SingleComment { doc } => {
+                    has_comment = true;
+                    has_doc |= doc;
+                    ends_in_comment = true;
+                    break;
+                }
+                Token::MultiComment { pair, doc } => {
+                    has_comment = true;
+                    state.in_multi_line_comment = true;
+                    state.current_delimiter = Some(if doc {
+                        self.nesting_pair(pair).clone()
+                    } else {
+                        pair.clone()
+                    });
+                    state.in_doc_comment = doc;
+                    has_doc |= doc;
+                    state.comment_depth = 1;
+                    pos += token_pos + pair.start.len();
+                }
+                Token::String(string) => {
+                    // A multi-line string that's a statement of its own is a docstring
+                    let docstring = self.language.docstrings
+                        && string.multi_line
+                        && !has_code
+                        && state.open_brackets == 0
+                        && !state.continued_line;
+                    if docstring {
+                        has_doc = true;
+                    } else {
+                        has_code = true;
+                    }
+                    pos += token_pos + string.start.len();
+                    // A single-line string must end on this line, otherwise
+                    // the quote isn't a string start (e.g. an apostrophe)
+                    if string.multi_line || Self::find_string_end(&line[pos..], string).is_some() {
+                        state.current_string = Some(string.clone());
+                        state.in_docstring = docstring;
+                    }
                 }
             }
         }
 
-        // No comments found, it's code
-        LineType::Code
+        state.continued_line = self.language.docstrings
+            && state.current_string.is_none()
+            && !ends_in_comment
+            && line.trim_end().ends_with('\\');
+
+        if has_code && has_comment {
+            LineType::Mixed
+        } else if has_code {
+            LineType::Code
+        } else if has_doc {
+            LineType::Doc
+        } else {
+            LineType::Comment
+        }
     }
 }
 
 /// Classify all lines in a file and return statistics.
 pub fn classify_file(lines: &[String], language: &LanguageInfo) -> FileStats {
-    let classifier = LineClassifier::new(language);
-    let mut state = ClassifierState::new();
     let mut stats = FileStats::new();
 
-    for line in lines {
-        match classifier.classify_line(line, &mut state) {
+    for line_type in classify_lines(lines, language) {
+        match line_type {
             LineType::Blank => stats.blank += 1,
             LineType::Comment => stats.comment += 1,
+            LineType::Doc => stats.doc += 1,
             LineType::Code => stats.code += 1,
+            LineType::Mixed => stats.mixed += 1,
         }
     }
 
     stats
 }
 
+/// Classify all lines in a file and return the type of each line, in order.
+///
+/// Multi-line comment state is carried from one line to the next.
+pub fn classify_lines<S: AsRef<str>>(lines: &[S], language: &LanguageInfo) -> Vec<LineType> {
+    let classifier = LineClassifier::new(language);
+    let mut state = ClassifierState::new();
+
+    lines
+        .iter()
+        .map(|line| classifier.classify_line(line.as_ref(), &mut state))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +435,18 @@ mod tests {
         LANGUAGES.iter().find(|l| l.name == "Python").unwrap()
     }
 
+    fn get_c_language() -> &'static LanguageInfo {
+        LANGUAGES.iter().find(|l| l.name == "C").unwrap()
+    }
+
+    fn get_haskell_language() -> &'static LanguageInfo {
+        LANGUAGES.iter().find(|l| l.name == "Haskell").unwrap()
+    }
+
+    fn get_javascript_language() -> &'static LanguageInfo {
+        LANGUAGES.iter().find(|l| l.name == "JavaScript").unwrap()
+    }
+
     #[test]
     fn test_line_type_equality() {
         assert_eq!(LineType::Blank, LineType::Blank);
@@ -229,10 +467,12 @@ mod tests {
         let mut state = ClassifierState::new();
         state.in_multi_line_comment = true;
         state.current_delimiter = Some(CommentPair::new("/*", "*/"));
+        state.comment_depth = 2;
 
         state.reset();
         assert!(!state.in_multi_line_comment);
         assert!(state.current_delimiter.is_none());
+        assert_eq!(state.comment_depth, 0);
     }
 
     #[test]
@@ -269,10 +509,14 @@ mod tests {
         );
         assert_eq!(
             classifier.classify_line("/// doc comment", &mut state),
-            LineType::Comment
+            LineType::Doc
         );
         assert_eq!(
             classifier.classify_line("//! inner doc comment", &mut state),
+            LineType::Doc
+        );
+        assert_eq!(
+            classifier.classify_line("//// not a doc comment", &mut state),
             LineType::Comment
         );
     }
@@ -303,10 +547,10 @@ mod tests {
         let classifier = LineClassifier::new(lang);
         let mut state = ClassifierState::new();
 
-        // Code with trailing comment should be Code
+        // Code with trailing comment is Mixed
         assert_eq!(
             classifier.classify_line("let x = 5; // comment", &mut state),
-            LineType::Code
+            LineType::Mixed
         );
     }
 
@@ -336,13 +580,13 @@ mod tests {
         // Multi-line comment with code after
         assert_eq!(
             classifier.classify_line("/* comment */ let x = 5;", &mut state),
-            LineType::Code
+            LineType::Mixed
         );
 
         // Code with multi-line comment after
         assert_eq!(
             classifier.classify_line("let x = 5; /* comment */", &mut state),
-            LineType::Code
+            LineType::Mixed
         );
     }
 
@@ -386,7 +630,7 @@ mod tests {
         );
         assert_eq!(
             classifier.classify_line("more comment */ let x = 5;", &mut state),
-            LineType::Code
+            LineType::Mixed
         );
         assert!(!state.in_multi_line_comment);
     }
@@ -412,23 +656,75 @@ mod tests {
         // Single line docstring
         assert_eq!(
             classifier.classify_line("\"\"\"This is a docstring\"\"\"", &mut state),
-            LineType::Comment
+            LineType::Doc
         );
 
         // Multi-line docstring
         state.reset();
         assert_eq!(
             classifier.classify_line("\"\"\"", &mut state),
-            LineType::Comment
+            LineType::Doc
         );
         assert_eq!(
             classifier.classify_line("Docstring content", &mut state),
-            LineType::Comment
+            LineType::Doc
         );
         assert_eq!(
             classifier.classify_line("\"\"\"", &mut state),
+            LineType::Doc
+        );
+    }
+
+    #[test]
+    fn test_classify_doc_comments() {
+        let lang = get_rust_language();
+        let classifier = LineClassifier::new(lang);
+        let mut state = ClassifierState::new();
+
+        assert_eq!(
+            classifier.classify_line("/** Outer doc", &mut state),
+            LineType::Doc
+        );
+        assert_eq!(
+            classifier.classify_line("    /* nested */ still doc", &mut state),
+            LineType::Doc
+        );
+        assert_eq!(classifier.classify_line("*/", &mut state), LineType::Doc);
+        assert_eq!(
+            classifier.classify_line("/*! Inner doc */", &mut state),
+            LineType::Doc
+        );
+
+        // Empty and banner comments aren't documentation
+        assert_eq!(
+            classifier.classify_line("/**/", &mut state),
             LineType::Comment
         );
+        assert_eq!(
+            classifier.classify_line("/*** banner ***/", &mut state),
+            LineType::Comment
+        );
+        assert_eq!(
+            classifier.classify_line("// plain /// comment", &mut state),
+            LineType::Comment
+        );
+        assert_eq!(
+            classifier.classify_line("let x = 1; /// doc", &mut state),
+            LineType::Mixed
+        );
+
+        let haskell = LineClassifier::new(get_haskell_language());
+        state.reset();
+        assert_eq!(
+            haskell.classify_line("-- | Haddock", &mut state),
+            LineType::Doc
+        );
+        assert_eq!(
+            haskell.classify_line("-- plain", &mut state),
+            LineType::Comment
+        );
+        assert_eq!(haskell.classify_line("{- |", &mut state), LineType::Doc);
+        assert_eq!(haskell.classify_line("-}", &mut state), LineType::Doc);
     }
 
     #[test]
@@ -448,7 +744,25 @@ mod tests {
         let stats = classify_file(&lines, lang);
         assert_eq!(stats.blank, 1);
         assert_eq!(stats.comment, 3); // header + 2 lines of multi-line comment
-        assert_eq!(stats.code, 4); // main, let x (with comment), println, }
+        assert_eq!(stats.code, 3); // main, println, }
+        assert_eq!(stats.mixed, 1); // let x (with comment)
+    }
+
+    #[test]
+    fn test_classify_lines() {
+        let lang = get_rust_language();
+        let lines = ["/* start", "middle", "end */", "", "let x = 5;"];
+
+        assert_eq!(
+            classify_lines(&lines, lang),
+            vec![
+                LineType::Comment,
+                LineType::Comment,
+                LineType::Comment,
+                LineType::Blank,
+                LineType::Code,
+            ]
+        );
     }
 
     #[test]
@@ -472,7 +786,7 @@ mod tests {
         // Code before multi-line comment start
         assert_eq!(
             classifier.classify_line("let x = 5; /* comment", &mut state),
-            LineType::Code
+            LineType::Mixed
         );
         assert!(state.in_multi_line_comment);
 
@@ -481,4 +795,240 @@ mod tests {
             LineType::Comment
         );
     }
+
+    #[test]
+    fn test_comment_markers_in_strings() {
+        let lang = get_rust_language();
+        let classifier = LineClassifier::new(lang);
+        let mut state = ClassifierState::new();
+
+        assert_eq!(
+            classifier.classify_line("let url = \"http://x/*\";", &mut state),
+            LineType::Code
+        );
+        assert!(!state.in_multi_line_comment);
+        assert_eq!(
+            classifier.classify_line("let s = \"a \\\" /* b\"; /* real", &mut state),
+            LineType::Mixed
+        );
+        assert!(state.in_multi_line_comment);
+        assert_eq!(
+            classifier.classify_line("comment */", &mut state),
+            LineType::Comment
+        );
+
+        // Raw strings have no escapes and may contain quotes
+        assert_eq!(
+            classifier.classify_line("let r = r#\"\\\" /* \"#; // done", &mut state),
+            LineType::Mixed
+        );
+        assert!(!state.in_multi_line_comment);
+
+        // Char literals and lifetimes
+        assert_eq!(
+            classifier.classify_line("let q = '\"'; /* c */", &mut state),
+            LineType::Mixed
+        );
+        assert_eq!(
+            classifier.classify_line("fn f<'a>(x: &'a str) {} /* c", &mut state),
+            LineType::Mixed
+        );
+        assert!(state.in_multi_line_comment);
+        assert_eq!(
+            classifier.classify_line("*/", &mut state),
+            LineType::Comment
+        );
+
+        // A lifetime doesn't pair with the apostrophe of a later string
+        assert_eq!(
+            classifier.classify_line("fn f<'a>(x: &'a str) -> &'a str { \"it's\" }", &mut state),
+            LineType::Code
+        );
+        assert!(state.current_string.is_none());
+        assert_eq!(
+            classifier.classify_line("/// Doc comment", &mut state),
+            LineType::Doc
+        );
+        assert_eq!(
+            classifier.classify_line("'outer: loop { break 'outer; } // done", &mut state),
+            LineType::Mixed
+        );
+        assert_eq!(
+            classifier.classify_line(
+                "let c = ['\\n', '\\u{2F}', '\\x2F', '/']; // done",
+                &mut state
+            ),
+            LineType::Mixed
+        );
+    }
+
+    #[test]
+    fn test_multi_line_strings() {
+        let lang = get_javascript_language();
+        let lines = [
+            "const sql = `",
+            "  // not a comment",
+            "  /* nor this`; // but this is",
+            "// comment",
+        ];
+
+        assert_eq!(
+            classify_lines(&lines, lang),
+            vec![
+                LineType::Code,
+                LineType::Code,
+                LineType::Mixed,
+                LineType::Comment,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_quote_is_not_a_string() {
+        let lang = get_python_language();
+        let classifier = LineClassifier::new(lang);
+        let mut state = ClassifierState::new();
+
+        assert_eq!(
+            classifier.classify_line("s = \"# not a comment\"", &mut state),
+            LineType::Code
+        );
+        assert_eq!(
+            classifier.classify_line("# it's a comment", &mut state),
+            LineType::Comment
+        );
+        // A stray quote doesn't swallow the comment after it
+        assert_eq!(
+            classifier.classify_line("x = y' # z", &mut state),
+            LineType::Mixed
+        );
+        assert_eq!(
+            classifier.classify_line("# next", &mut state),
+            LineType::Comment
+        );
+    }
+
+    #[test]
+    fn test_python_triple_quoted_string() {
+        let lang = get_python_language();
+        let lines = [
+            "query = \"\"\"",
+            "# part of the string",
+            "\"\"\"",
+            "# comment",
+        ];
+
+        assert_eq!(
+            classify_lines(&lines, lang),
+            vec![
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+                LineType::Comment,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_docstring_position() {
+        let lang = get_python_language();
+        let lines = [
+            "\"\"\"Module docstring.\"\"\"",
+            "def query(cursor):",
+            "    \"\"\"",
+            "    Function docstring.",
+            "    \"\"\"",
+            "    cursor.execute(",
+            "        \"\"\"",
+            "        SELECT 1",
+            "        \"\"\"",
+            "    )",
+            "    \"\"\"Standalone string.\"\"\"  # note",
+            "    sql = \\",
+            "        \"\"\"SELECT 2\"\"\"",
+            "    return \"\"\"{}\"\"\".format(sql)",
+            "    \"\"\"Not a docstring\"\"\".strip()",
+            "    f\"\"\"{sql}\"\"\"",
+        ];
+
+        assert_eq!(
+            classify_lines(&lines, lang),
+            vec![
+                LineType::Doc,
+                LineType::Code,
+                LineType::Doc,
+                LineType::Doc,
+                LineType::Doc,
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+                LineType::Doc,
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+                LineType::Code,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_comments() {
+        let lines = [
+            "/* outer",
+            "   /* inner */",
+            "   still outer */",
+            "let x = 5; /* a /* b */ c */ let y = 6;",
+            "/* /* */ */",
+        ];
+
+        assert_eq!(
+            classify_lines(&lines, get_rust_language()),
+            vec![
+                LineType::Comment,
+                LineType::Comment,
+                LineType::Comment,
+                LineType::Mixed,
+                LineType::Comment,
+            ]
+        );
+
+        // In C the first end closes the comment
+        assert_eq!(
+            classify_lines(&lines, get_c_language()),
+            vec![
+                LineType::Comment,
+                LineType::Comment,
+                LineType::Code,
+                LineType::Mixed,
+                LineType::Mixed,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_comments_depth() {
+        let lang = get_haskell_language();
+        let classifier = LineClassifier::new(lang);
+        let mut state = ClassifierState::new();
+
+        assert_eq!(
+            classifier.classify_line("{- {- {-", &mut state),
+            LineType::Comment
+        );
+        assert_eq!(state.comment_depth, 3);
+        assert_eq!(
+            classifier.classify_line("-} -}", &mut state),
+            LineType::Comment
+        );
+        assert!(state.in_multi_line_comment);
+        assert_eq!(
+            classifier.classify_line("-} main = pure ()", &mut state),
+            LineType::Mixed
+        );
+        assert!(!state.in_multi_line_comment);
+        assert_eq!(state.comment_depth, 0);
+    }
 }
@@ -3,7 +3,12 @@
 //! This module defines the CLI structure and handles
 //! parsing and validation of command-line arguments.
 
-use chrono::{DateTime, Utc};
+use crate::git::{
+    AuthorFilter, CoAuthorCredit, DateSource, MergePolicy, Period, Timezone,
+    DEFAULT_RENAME_THRESHOLD,
+};
+use crate::stats::MixedLines;
+use chrono::{DateTime, NaiveDateTime};
 use clap::Parser;
 use std::path::PathBuf;
 
@@ -28,6 +33,18 @@ pub struct Cli {
     #[arg(long)]
     pub history: bool,
 
+    /// Track the size of the codebase over git history
+    #[arg(long, conflicts_with_all = ["history", "at"])]
+    pub timeline: bool,
+
+    /// Count lines of code at a git revision (e.g. v2.3) instead of the working tree
+    #[arg(long, value_name = "REV", conflicts_with = "history")]
+    pub at: Option<String>,
+
+    /// Attribute today's code lines to their authors with git blame
+    #[arg(long, conflicts_with_all = ["history", "timeline", "at"])]
+    pub ownership: bool,
+
     /// Only analyze commits since this date (format: YYYY-MM-DD or RFC3339)
     #[arg(long, value_name = "DATE")]
     pub since: Option<String>,
@@ -40,6 +57,14 @@ pub struct Cli {
     #[arg(long, value_name = "N", conflicts_with_all = ["since", "until"])]
     pub last: Option<usize>,
 
+    /// Time zone for commit dates and --since/--until (commit, utc, local, or an offset like +02:00) [default: commit]
+    #[arg(long, value_name = "ZONE")]
+    pub timezone: Option<Timezone>,
+
+    /// Which commit date to bucket and filter history by [default: committer]
+    #[arg(long, value_enum, ignore_case = true, value_name = "DATE")]
+    pub date: Option<DateSource>,
+
     /// Group history by day (default)
     #[arg(long)]
     pub by_day: bool,
@@ -48,14 +73,74 @@ pub struct Cli {
     #[arg(long)]
     pub by_week: bool,
 
-    /// Filter commits by author name
-    #[arg(long, value_name = "NAME")]
-    pub author: Option<String>,
+    /// Group history by month
+    #[arg(long)]
+    pub by_month: bool,
+
+    /// Group history by quarter
+    #[arg(long)]
+    pub by_quarter: bool,
+
+    /// Group history by year
+    #[arg(long)]
+    pub by_year: bool,
+
+    /// Maximum number of periods shown in history tables (0 = all)
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Split history additions and deletions by language
+    #[arg(long)]
+    pub by_language: bool,
+
+    /// Show the N files changed most often instead of the history summary
+    #[arg(long, value_name = "N", conflicts_with = "by_language")]
+    pub hotspots: Option<usize>,
+
+    /// Rank hotspots by commits times current lines of code
+    #[arg(long, requires = "hotspots")]
+    pub weight_by_size: bool,
+
+    /// Analyze a revision or range instead of HEAD (e.g. main, v1.0..v2.0)
+    #[arg(long, value_name = "REV")]
+    pub rev: Option<String>,
+
+    /// Analyze commits from every branch
+    #[arg(long)]
+    pub all: bool,
+
+    /// Filter commits by author name or email (regex, can be used multiple times)
+    #[arg(long, value_name = "PATTERN")]
+    pub author: Vec<String>,
+
+    /// Group author statistics by email instead of name
+    #[arg(long)]
+    pub group_by_email: bool,
+
+    /// How to credit Co-authored-by trailers [default: full]
+    #[arg(long, value_enum, ignore_case = true, value_name = "MODE")]
+    pub co_author_credit: Option<CoAuthorCredit>,
+
+    /// How to handle merge commits in history [default: skip]
+    #[arg(long, value_enum, ignore_case = true, value_name = "POLICY")]
+    pub merges: Option<MergePolicy>,
+
+    /// Similarity percentage for detecting renamed and copied files in history [default: 50]
+    #[arg(long, value_name = "PERCENT")]
+    pub rename_threshold: Option<u16>,
+
+    /// Disable rename and copy detection in history
+    #[arg(long)]
+    pub no_renames: bool,
 
     /// Output format (table, json, or csv)
     #[arg(long, default_value = "table", value_name = "FORMAT")]
     pub format: String,
 
+    /// How to count lines with both code and comments (code, comment, both, or separate)
+    #[arg(long, default_value = "code", value_name = "POLICY")]
+    pub mixed_lines: String,
+
     /// Number of parallel jobs (0 = number of CPUs)
     #[arg(short = 'j', long, default_value = "0", value_name = "N")]
     pub jobs: usize,
@@ -87,26 +172,79 @@ impl Cli {
             }
         }
 
-        // Validate that --by-day and --by-week are mutually exclusive
-        if self.by_day && self.by_week {
-            return Err("Cannot use both --by-day and --by-week".to_string());
+        // Validate that the period flags are mutually exclusive
+        let periods = [
+            self.by_day,
+            self.by_week,
+            self.by_month,
+            self.by_quarter,
+            self.by_year,
+        ];
+        if periods.iter().filter(|&&set| set).count() > 1 {
+            return Err(
+                "Cannot use more than one of --by-day, --by-week, --by-month, --by-quarter and --by-year"
+                    .to_string(),
+            );
         }
 
-        // Validate that history-related flags require --history
+        // Validate that date and revision flags require --history or --timeline
         if !self.history
+            && !self.timeline
             && (self.since.is_some()
                 || self.until.is_some()
                 || self.last.is_some()
                 || self.by_day
                 || self.by_week
-                || self.author.is_some())
+                || self.by_month
+                || self.by_quarter
+                || self.by_year
+                || self.limit.is_some()
+                || self.rev.is_some()
+                || self.timezone.is_some()
+                || self.date.is_some())
         {
             return Err(
-                "History-related flags (--since, --until, --last, --by-day, --by-week, --author) require --history"
+                "History-related flags (--since, --until, --last, --timezone, --date, --by-day, --by-week, --by-month, --by-quarter, --by-year, --limit, --rev) require --history or --timeline"
                     .to_string(),
             );
         }
 
+        // Validate that commit-level flags require --history
+        if !self.history
+            && (self.by_language
+                || self.hotspots.is_some()
+                || self.all
+                || !self.author.is_empty()
+                || self.co_author_credit.is_some()
+                || self.merges.is_some()
+                || self.rename_threshold.is_some()
+                || self.no_renames)
+        {
+            return Err(
+                "History-related flags (--by-language, --hotspots, --all, --author, --co-author-credit, --merges, --rename-threshold, --no-renames) require --history"
+                    .to_string(),
+            );
+        }
+
+        // Validate that author grouping has authors to group
+        if !self.history && !self.ownership && self.group_by_email {
+            return Err("--group-by-email requires --history or --ownership".to_string());
+        }
+
+        // Validate author patterns
+        self.author_filter()?;
+
+        // Validate mixed lines policy
+        self.mixed_lines.parse::<MixedLines>()?;
+
+        // Validate rename threshold
+        if let Some(threshold) = self.rename_threshold.filter(|&t| t > 100) {
+            return Err(format!(
+                "Invalid rename threshold {}. Must be between 0 and 100",
+                threshold
+            ));
+        }
+
         // Validate format
         let format_lower = self.format.to_lowercase();
         if !["table", "json", "csv"].contains(&format_lower.as_str()) {
@@ -140,12 +278,64 @@ impl Cli {
         true
     }
 
-    /// Parse the --since date string into a `DateTime<Utc>`.
-    /// If --last N is specified, calculates the date N days ago.
-    pub fn parse_since_date(&self) -> Result<Option<DateTime<Utc>>, String> {
+    /// Parse the --author patterns into a filter.
+    pub fn author_filter(&self) -> Result<AuthorFilter, String> {
+        AuthorFilter::new(&self.author)
+    }
+
+    /// Parse the --mixed-lines policy.
+    pub fn mixed_lines(&self) -> Result<MixedLines, String> {
+        self.mixed_lines.parse()
+    }
+
+    /// Get the period selected by the --by-* flags (daily by default).
+    pub fn period(&self) -> Period {
+        if self.by_week {
+            Period::Week
+        } else if self.by_month {
+            Period::Month
+        } else if self.by_quarter {
+            Period::Quarter
+        } else if self.by_year {
+            Period::Year
+        } else {
+            Period::Day
+        }
+    }
+
+    /// Get the number of periods to show in history tables, or None for all.
+    ///
+    /// Defaults to the last 30 days, 12 weeks, 12 months, 8 quarters or every year.
+    pub fn row_limit(&self) -> Option<usize> {
+        match self.limit {
+            Some(0) => None,
+            Some(limit) => Some(limit),
+            None => match self.period() {
+                Period::Day => Some(30),
+                Period::Week | Period::Month => Some(12),
+                Period::Quarter => Some(8),
+                Period::Year => None,
+            },
+        }
+    }
+
+    /// Get the rename detection threshold, or None if detection is disabled.
+    pub fn rename_threshold(&self) -> Option<u16> {
+        if self.no_renames {
+            None
+        } else {
+            Some(self.rename_threshold.unwrap_or(DEFAULT_RENAME_THRESHOLD))
+        }
+    }
+
+    /// Parse the --since date string into a wall-clock time in the --timezone.
+    /// If --last N is specified, calculates the time N days ago.
+    pub fn parse_since_date(&self) -> Result<Option<NaiveDateTime>, String> {
+        let timezone = self.timezone.unwrap_or_default();
+
         // Handle --last N days
         if let Some(days) = self.last {
-            let now = Utc::now();
+            let now = timezone.now();
             let duration = chrono::Duration::days(days as i64);
             return Ok(Some(now - duration));
         }
@@ -154,29 +344,33 @@ impl Cli {
             return Ok(None);
         };
 
-        Self::parse_date_string(since_str)
+        Self::parse_date_string(since_str, timezone)
     }
 
-    /// Parse the --until date string into a `DateTime<Utc>`.
-    pub fn parse_until_date(&self) -> Result<Option<DateTime<Utc>>, String> {
+    /// Parse the --until date string into a wall-clock time in the --timezone.
+    pub fn parse_until_date(&self) -> Result<Option<NaiveDateTime>, String> {
         let Some(until_str) = &self.until else {
             return Ok(None);
         };
 
-        Self::parse_date_string(until_str)
+        Self::parse_date_string(until_str, self.timezone.unwrap_or_default())
     }
 
     /// Parse a date string in either RFC3339 or YYYY-MM-DD format.
-    fn parse_date_string(date_str: &str) -> Result<Option<DateTime<Utc>>, String> {
+    ///
+    /// Plain dates are midnight in `timezone`; RFC3339 times are converted to it.
+    fn parse_date_string(
+        date_str: &str,
+        timezone: Timezone,
+    ) -> Result<Option<NaiveDateTime>, String> {
         // Try to parse as RFC3339 first
         if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-            return Ok(Some(dt.with_timezone(&Utc)));
+            return Ok(Some(timezone.wall_clock(dt)));
         }
 
         // Try to parse as YYYY-MM-DD
         if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-            let naive_datetime = naive_date.and_hms_opt(0, 0, 0).unwrap();
-            return Ok(Some(naive_datetime.and_utc()));
+            return Ok(Some(naive_date.and_hms_opt(0, 0, 0).unwrap()));
         }
 
         Err(format!(
@@ -192,7 +386,7 @@ mod tests {
 
     #[test]
     fn test_parse_date_string_yyyy_mm_dd() {
-        let result = Cli::parse_date_string("2024-01-15");
+        let result = Cli::parse_date_string("2024-01-15", Timezone::Utc);
         assert!(result.is_ok());
         let dt = result.unwrap().unwrap();
         assert_eq!(dt.format("%Y-%m-%d").to_string(), "2024-01-15");
@@ -200,22 +394,41 @@ mod tests {
 
     #[test]
     fn test_parse_date_string_rfc3339() {
-        let result = Cli::parse_date_string("2024-01-15T10:30:00Z");
+        let result = Cli::parse_date_string("2024-01-15T10:30:00Z", Timezone::Utc);
         assert!(result.is_ok());
         let dt = result.unwrap().unwrap();
         assert_eq!(dt.format("%Y-%m-%d").to_string(), "2024-01-15");
     }
 
+    #[test]
+    fn test_parse_date_string_timezone() {
+        let late = "2024-01-15T23:30:00+02:00";
+        let parse = |zone: &str| {
+            Cli::parse_date_string(late, zone.parse().unwrap())
+                .unwrap()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(parse("commit"), "2024-01-15 23:30:00");
+        assert_eq!(parse("utc"), "2024-01-15 21:30:00");
+        assert_eq!(parse("-05:00"), "2024-01-15 16:30:00");
+
+        // Plain dates are midnight wherever they're interpreted
+        let date = Cli::parse_date_string("2024-01-15", "+09:00".parse().unwrap());
+        assert_eq!(date.unwrap().unwrap().to_string(), "2024-01-15 00:00:00");
+        assert!("Mars/Olympus".parse::<Timezone>().is_err());
+    }
+
     #[test]
     fn test_parse_date_string_invalid() {
-        let result = Cli::parse_date_string("not-a-date");
+        let result = Cli::parse_date_string("not-a-date", Timezone::Utc);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Invalid date format"));
     }
 
     #[test]
     fn test_parse_date_string_invalid_format() {
-        let result = Cli::parse_date_string("01/15/2024");
+        let result = Cli::parse_date_string("01/15/2024", Timezone::Utc);
         assert!(result.is_err());
     }
 
@@ -229,13 +442,33 @@ mod tests {
             hidden: false,
             verbose: false,
             history: false,
+            timeline: false,
+            at: None,
+            ownership: false,
             since: None,
             until: None,
             last: None,
             by_day: false,
             by_week: false,
-            author: None,
+            by_month: false,
+            by_quarter: false,
+            by_year: false,
+            limit: None,
+            by_language: false,
+            hotspots: None,
+            weight_by_size: false,
+            rev: None,
+            all: false,
+            author: vec![],
+            group_by_email: false,
+            timezone: None,
+            date: None,
+            co_author_credit: None,
+            merges: None,
+            rename_threshold: None,
+            no_renames: false,
             format: "table".to_string(),
+            mixed_lines: "code".to_string(),
             jobs: 0,
             no_color: false,
             exclude: vec![],
@@ -254,13 +487,33 @@ mod tests {
             hidden: false,
             verbose: false,
             history: false,
+            timeline: false,
+            at: None,
+            ownership: false,
             since: None,
             until: None,
             last: None,
             by_day: false,
             by_week: false,
-            author: None,
+            by_month: false,
+            by_quarter: false,
+            by_year: false,
+            limit: None,
+            by_language: false,
+            hotspots: None,
+            weight_by_size: false,
+            rev: None,
+            all: false,
+            author: vec![],
+            group_by_email: false,
+            timezone: None,
+            date: None,
+            co_author_credit: None,
+            merges: None,
+            rename_threshold: None,
+            no_renames: false,
             format: "table".to_string(),
+            mixed_lines: "code".to_string(),
             jobs: 0,
             no_color: true,
             exclude: vec![],
@@ -279,13 +532,33 @@ mod tests {
             hidden: false,
             verbose: false,
             history: false,
+            timeline: false,
+            at: None,
+            ownership: false,
             since: None,
             until: None,
             last: None,
             by_day: false,
             by_week: false,
-            author: None,
+            by_month: false,
+            by_quarter: false,
+            by_year: false,
+            limit: None,
+            by_language: false,
+            hotspots: None,
+            weight_by_size: false,
+            rev: None,
+            all: false,
+            author: vec![],
+            group_by_email: false,
+            timezone: None,
+            date: None,
+            co_author_credit: None,
+            merges: None,
+            rename_threshold: None,
+            no_renames: false,
             format: "table".to_string(),
+            mixed_lines: "code".to_string(),
             jobs: 0,
             no_color: false,
             exclude: vec![],
@@ -307,13 +580,33 @@ mod tests {
             hidden: false,
             verbose: false,
             history: false,
+            timeline: false,
+            at: None,
+            ownership: false,
             since: None,
             until: None,
             last: None,
             by_day: false,
             by_week: false,
-            author: None,
+            by_month: false,
+            by_quarter: false,
+            by_year: false,
+            limit: None,
+            by_language: false,
+            hotspots: None,
+            weight_by_size: false,
+            rev: None,
+            all: false,
+            author: vec![],
+            group_by_email: false,
+            timezone: None,
+            date: None,
+            co_author_credit: None,
+            merges: None,
+            rename_threshold: None,
+            no_renames: false,
             format: "table".to_string(),
+            mixed_lines: "code".to_string(),
             jobs: 0,
             no_color: false,
             exclude: vec![],
@@ -326,4 +619,29 @@ mod tests {
         // Clean up
         std::env::remove_var("NO_COLOR");
     }
+
+    #[test]
+    fn test_row_limit() {
+        let parse =
+            |args: &[&str]| Cli::try_parse_from([&["sniffy", "--history"], args].concat()).unwrap();
+
+        assert_eq!(parse(&[]).row_limit(), Some(30));
+        assert_eq!(parse(&["--by-week"]).row_limit(), Some(12));
+        assert_eq!(parse(&["--by-quarter"]).row_limit(), Some(8));
+        assert_eq!(parse(&["--by-year"]).row_limit(), None);
+        assert_eq!(parse(&["--by-year", "--limit", "3"]).row_limit(), Some(3));
+        assert_eq!(parse(&["--limit", "0"]).row_limit(), None);
+        assert_eq!(parse(&["--by-month"]).period(), Period::Month);
+    }
+
+    #[test]
+    fn test_mixed_lines_policy() {
+        let parse = |args: &[&str]| Cli::try_parse_from([&["sniffy"], args].concat()).unwrap();
+
+        assert_eq!(parse(&[]).mixed_lines(), Ok(MixedLines::Code));
+        let cli = parse(&["--mixed-lines", "Separate"]);
+        assert!(cli.validate().is_ok());
+        assert_eq!(cli.mixed_lines(), Ok(MixedLines::Separate));
+        assert!(parse(&["--mixed-lines", "half"]).validate().is_err());
+    }
 }
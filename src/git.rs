@@ -3,13 +3,25 @@
 //! This module provides functionality for analyzing git commit history
 //! to track code changes over time.
 
-use crate::classifier::LineType;
-use crate::stats::FileStats;
-use chrono::{DateTime, NaiveDate, Utc};
-use git2::Repository;
+use crate::classifier::{classify_lines, LineType};
+use crate::language::{LanguageDetector, LanguageInfo};
+use crate::ownership::{Ownership, OwnershipStats};
+use crate::processor::FileProcessor;
+use crate::stats::{FileStats, MixedLines, ProjectStats};
+use crate::walker::should_skip_file;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime};
+use clap::ValueEnum;
+use git2::{
+    Delta, DiffFindOptions, DiffOptions, Mailmap, ObjectType, Oid, Patch, Repository, RevparseMode,
+    Revwalk, Signature, TreeWalkMode, TreeWalkResult,
+};
+use ignore::overrides::{Override, OverrideBuilder};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 /// Daily statistics for git commits.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -18,61 +30,649 @@ pub struct DailyStats {
     pub additions: FileStats,
     pub deletions: FileStats,
     pub net_code: i64,
+    /// Additions and deletions split by language name.
+    #[serde(skip)]
+    pub by_language: HashMap<String, LineChanges>,
+}
+
+impl DailyStats {
+    /// Add another set of changes into this one.
+    fn add(&mut self, other: &DailyStats) {
+        self.additions += other.additions;
+        self.deletions += other.deletions;
+        self.net_code += other.net_code;
+        for (language, changes) in &other.by_language {
+            *self.by_language.entry(language.clone()).or_default() += *changes;
+        }
+    }
+}
+
+/// Lines added and deleted, such as in a single language or file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineChanges {
+    pub additions: FileStats,
+    pub deletions: FileStats,
+}
+
+impl LineChanges {
+    /// Net change in code lines (additions minus deletions).
+    pub fn net_code(&self) -> i64 {
+        self.additions.code as i64 - self.deletions.code as i64
+    }
+}
+
+impl AddAssign for LineChanges {
+    fn add_assign(&mut self, other: Self) {
+        self.additions += other.additions;
+        self.deletions += other.deletions;
+    }
+}
+
+/// Statistics for a single author across the analyzed history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorStats {
+    /// Lines added by the author.
+    pub additions: FileStats,
+    /// Lines deleted by the author.
+    pub deletions: FileStats,
+    /// Net change in code lines.
+    pub net_code: i64,
+    /// Number of commits authored.
+    pub commits: usize,
+    /// Number of commits credited through a `Co-authored-by:` trailer.
+    pub co_authored_commits: usize,
+    /// Number of distinct days with at least one commit.
+    pub active_days: usize,
+    /// Date of the author's first commit in the analyzed range.
+    pub first_seen: Option<NaiveDate>,
+    /// Date of the author's last commit in the analyzed range.
+    pub last_seen: Option<NaiveDate>,
+    /// Days with commits, used to count active days.
+    #[serde(skip)]
+    days: HashSet<NaiveDate>,
+}
+
+impl AuthorStats {
+    /// Record a commit made on `date` with the given line changes.
+    pub fn record_commit(&mut self, date: NaiveDate, additions: FileStats, deletions: FileStats) {
+        self.commits += 1;
+        self.record_changes(date, additions, deletions);
+    }
+
+    /// Record a commit co-authored on `date`, credited with the given line changes.
+    pub fn record_co_authored(
+        &mut self,
+        date: NaiveDate,
+        additions: FileStats,
+        deletions: FileStats,
+    ) {
+        self.co_authored_commits += 1;
+        self.record_changes(date, additions, deletions);
+    }
+
+    /// Add line changes made on `date`.
+    fn record_changes(&mut self, date: NaiveDate, additions: FileStats, deletions: FileStats) {
+        self.additions += additions;
+        self.deletions += deletions;
+        self.net_code += (additions.code as i64) - (deletions.code as i64);
+
+        self.days.insert(date);
+        self.active_days = self.days.len();
+        self.first_seen = Some(self.first_seen.map_or(date, |d| d.min(date)));
+        self.last_seen = Some(self.last_seen.map_or(date, |d| d.max(date)));
+    }
+}
+
+/// How commits with `Co-authored-by:` trailers are credited to their authors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CoAuthorCredit {
+    /// Ignore co-authors; only the commit author is credited.
+    None,
+    /// Credit the commit author and every co-author with all of the changes.
+    #[default]
+    Full,
+    /// Split the changes evenly between the commit author and co-authors.
+    Split,
+}
+
+impl CoAuthorCredit {
+    /// Divide a commit's line changes between its author and `co_authors` co-authors.
+    ///
+    /// Returns the author's share and each co-author's share. When splitting,
+    /// any remainder goes to the commit author.
+    pub fn share(self, stats: FileStats, co_authors: usize) -> (FileStats, FileStats) {
+        match self {
+            CoAuthorCredit::None => (stats, FileStats::default()),
+            CoAuthorCredit::Full => (stats, stats),
+            CoAuthorCredit::Split => {
+                let parts = co_authors + 1;
+                let co_share = FileStats {
+                    blank: stats.blank / parts,
+                    comment: stats.comment / parts,
+                    doc: stats.doc / parts,
+                    code: stats.code / parts,
+                    mixed: stats.mixed / parts,
+                };
+                let author_share = FileStats {
+                    blank: stats.blank - co_share.blank * co_authors,
+                    comment: stats.comment - co_share.comment * co_authors,
+                    doc: stats.doc - co_share.doc * co_authors,
+                    code: stats.code - co_share.code * co_authors,
+                    mixed: stats.mixed - co_share.mixed * co_authors,
+                };
+                (author_share, co_share)
+            }
+        }
+    }
+}
+
+/// Change statistics for a single file across the analyzed history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileChurn {
+    /// Number of commits that changed the file.
+    pub commits: usize,
+    /// Lines added to the file.
+    pub additions: FileStats,
+    /// Lines deleted from the file.
+    pub deletions: FileStats,
+    /// Number of distinct authors (and co-authors) who changed the file.
+    pub authors: usize,
+    /// Lines of code in the working tree copy, once measured with
+    /// [`GitAnalyzer::measure_hotspots`]. None if the file no longer exists.
+    pub current_code: Option<usize>,
+    #[serde(skip)]
+    author_keys: HashSet<String>,
+}
+
+impl FileChurn {
+    /// Record a commit's changes to the file by the given authors.
+    fn record_commit(&mut self, changes: LineChanges, authors: &[String]) {
+        self.commits += 1;
+        self.additions += changes.additions;
+        self.deletions += changes.deletions;
+        for author in authors {
+            if self.author_keys.insert(author.clone()) {
+                self.authors += 1;
+            }
+        }
+    }
+
+    /// Lines of code added plus lines of code deleted.
+    pub fn churn(&self) -> usize {
+        self.additions.code + self.deletions.code
+    }
+
+    /// Commits weighted by current size, so large files that change often rank highest.
+    pub fn weighted_score(&self) -> usize {
+        self.commits * self.current_code.unwrap_or(0)
+    }
 }
 
 /// Historical statistics aggregated from git history.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct HistoricalStats {
     pub daily: Vec<DailyStats>,
-    pub by_author: HashMap<String, FileStats>,
+    pub by_author: HashMap<String, AuthorStats>,
     pub total_commits: usize,
+    /// Number of merge commits skipped by the merge policy.
+    pub merges_skipped: usize,
+    /// Number of file renames detected (their unchanged lines are not counted).
+    pub renames: usize,
+    /// Number of file copies detected (their unchanged lines are not counted).
+    pub copies: usize,
+    /// Changes per file, keyed by the file's latest path.
+    pub by_file: HashMap<String, FileChurn>,
 }
 
 impl HistoricalStats {
+    /// Get authors sorted by contribution (total lines added, then name).
+    pub fn authors_by_contribution(&self) -> Vec<(&String, &AuthorStats)> {
+        let mut authors: Vec<_> = self.by_author.iter().collect();
+        authors.sort_by(|a, b| {
+            b.1.additions
+                .total()
+                .cmp(&a.1.additions.total())
+                .then_with(|| a.0.cmp(b.0))
+        });
+        authors
+    }
+
+    /// Get changes per language across the whole history, by lines of code added.
+    pub fn language_totals(&self) -> Vec<(String, LineChanges)> {
+        let mut totals: HashMap<String, LineChanges> = HashMap::new();
+        for daily in &self.daily {
+            for (language, changes) in &daily.by_language {
+                *totals.entry(language.clone()).or_default() += *changes;
+            }
+        }
+
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by(|a, b| {
+            b.1.additions
+                .code
+                .cmp(&a.1.additions.code)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        totals
+    }
+
+    /// Get files sorted as hotspots: by commits, then lines of code changed.
+    ///
+    /// If `weighted` is true, files are sorted by [`FileChurn::weighted_score`]
+    /// instead, and files that no longer exist are left out.
+    pub fn hotspots(&self, weighted: bool) -> Vec<(&String, &FileChurn)> {
+        let mut files: Vec<_> = self
+            .by_file
+            .iter()
+            .filter(|(_, churn)| !weighted || churn.current_code.is_some())
+            .collect();
+        files.sort_by(|a, b| {
+            let key = |churn: &FileChurn| {
+                if weighted {
+                    (churn.weighted_score(), churn.commits, churn.churn())
+                } else {
+                    (churn.commits, churn.churn(), 0)
+                }
+            };
+            key(b.1).cmp(&key(a.1)).then_with(|| a.0.cmp(b.0))
+        });
+        files
+    }
+
     /// Aggregate daily statistics by week (Monday-Sunday).
     /// Returns a new vector of DailyStats where each entry represents a week.
     pub fn aggregate_by_week(&self) -> Vec<DailyStats> {
-        use chrono::Datelike;
-
-        if self.daily.is_empty() {
-            return Vec::new();
-        }
+        self.aggregate(Period::Week)
+    }
 
-        let mut weekly: HashMap<(i32, u32), DailyStats> = HashMap::new();
+    /// Aggregate daily statistics into periods.
+    ///
+    /// Each entry is dated by the first day of its period, most recent first.
+    pub fn aggregate(&self, period: Period) -> Vec<DailyStats> {
+        let mut buckets: HashMap<NaiveDate, DailyStats> = HashMap::new();
 
         for daily in &self.daily {
-            // Get the ISO week number and year
-            let iso_week = daily.date.iso_week();
-            let year = iso_week.year();
-            let week = iso_week.week();
-            let key = (year, week);
-
-            // Get the Monday of this week as the representative date
-            let week_start = chrono::NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon)
-                .unwrap_or(daily.date);
-
-            let week_stat = weekly.entry(key).or_insert_with(|| DailyStats {
-                date: week_start,
-                additions: FileStats::default(),
-                deletions: FileStats::default(),
-                net_code: 0,
+            let start = period.bucket_start(daily.date);
+            let bucket = buckets.entry(start).or_insert_with(|| DailyStats {
+                date: start,
+                ..DailyStats::default()
             });
 
-            week_stat.additions += daily.additions;
-            week_stat.deletions += daily.deletions;
-            week_stat.net_code += daily.net_code;
+            bucket.add(daily);
         }
 
         // Convert to sorted vec
-        let mut result: Vec<_> = weekly.into_values().collect();
-        result.sort_by(|a, b| b.date.cmp(&a.date)); // Most recent first
+        let mut result: Vec<_> = buckets.into_values().collect();
+        result.sort_by_key(|b| std::cmp::Reverse(b.date)); // Most recent first
         result
     }
 }
 
+/// Matches commit authors by name or email.
+///
+/// Each pattern is a regular expression searched anywhere in the author's
+/// name or email, so plain names match as substrings.
+#[derive(Debug, Clone, Default)]
+pub struct AuthorFilter {
+    patterns: Vec<Regex>,
+}
+
+impl AuthorFilter {
+    /// Create a filter matching any of the given patterns.
+    ///
+    /// Returns an error if a pattern isn't a valid regular expression.
+    pub fn new(patterns: &[String]) -> Result<Self, String> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| format!("Invalid author pattern '{}': {}", pattern, e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Check if the filter has no patterns (matches every author).
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Check if an author with this name and email passes the filter.
+    pub fn matches(&self, name: &str, email: &str) -> bool {
+        self.is_empty()
+            || self
+                .patterns
+                .iter()
+                .any(|p| p.is_match(name) || p.is_match(email))
+    }
+}
+
+/// How merge commits are handled in history analysis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum MergePolicy {
+    /// Skip merge commits; their changes are counted in the merged commits.
+    #[default]
+    Skip,
+    /// Only walk first parents, diffing merges against their first parent.
+    FirstParent,
+    /// Diff merges against all parents, counting only the merge's own changes.
+    AllParents,
+}
+
+/// Which of a commit's timestamps history is bucketed and filtered by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DateSource {
+    /// When the commit was last written, e.g. by a rebase or cherry-pick.
+    #[default]
+    Committer,
+    /// When the change was originally authored.
+    Author,
+}
+
+/// Time zone that commit times are bucketed and filtered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Timezone {
+    /// Each commit's own recorded offset, i.e. the author's wall clock.
+    #[default]
+    Commit,
+    Utc,
+    /// The time zone of the machine running the analysis.
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Timezone {
+    /// Get the wall-clock time of a commit in this time zone.
+    pub fn commit_datetime(self, time: git2::Time) -> NaiveDateTime {
+        let utc = DateTime::from_timestamp(time.seconds(), 0).unwrap_or(DateTime::UNIX_EPOCH);
+        match self {
+            Timezone::Commit => match FixedOffset::east_opt(time.offset_minutes() * 60) {
+                Some(offset) => utc.with_timezone(&offset).naive_local(),
+                None => utc.naive_utc(),
+            },
+            _ => self.wall_clock(utc.fixed_offset()),
+        }
+    }
+
+    /// Get the wall-clock time of an instant in this time zone.
+    ///
+    /// With [`Timezone::Commit`] the instant keeps its own offset.
+    pub fn wall_clock(self, datetime: DateTime<FixedOffset>) -> NaiveDateTime {
+        match self {
+            Timezone::Commit => datetime.naive_local(),
+            Timezone::Utc => datetime.naive_utc(),
+            Timezone::Local => datetime.with_timezone(&Local).naive_local(),
+            Timezone::Fixed(offset) => datetime.with_timezone(&offset).naive_local(),
+        }
+    }
+
+    /// Get the current wall-clock time in this time zone.
+    ///
+    /// [`Timezone::Commit`] has no zone of its own and uses local time.
+    pub fn now(self) -> NaiveDateTime {
+        self.wall_clock(Local::now().fixed_offset())
+    }
+}
+
+impl FromStr for Timezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "commit" => Ok(Timezone::Commit),
+            "utc" => Ok(Timezone::Utc),
+            "local" => Ok(Timezone::Local),
+            _ => s.parse::<FixedOffset>().map(Timezone::Fixed).map_err(|_| {
+                format!(
+                    "Invalid time zone '{}'. Supported values: commit, utc, local, or an offset like +02:00",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+/// Calendar period used to bucket history by date.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Period {
+    #[default]
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+    Month,
+    /// Calendar quarters, starting in January, April, July and October.
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// Get the first day of the period containing `date`.
+    pub fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        use chrono::Datelike;
+
+        match self {
+            Period::Day => date,
+            Period::Week => {
+                date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+            }
+            Period::Month => date.with_day(1).unwrap_or(date),
+            Period::Quarter => {
+                NaiveDate::from_ymd_opt(date.year(), (date.month0() / 3) * 3 + 1, 1).unwrap_or(date)
+            }
+            Period::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap_or(date),
+        }
+    }
+
+    /// Get the label used for this period in output, such as "Weekly".
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Day => "Daily",
+            Period::Week => "Weekly",
+            Period::Month => "Monthly",
+            Period::Quarter => "Quarterly",
+            Period::Year => "Yearly",
+        }
+    }
+}
+
+/// Size of the codebase at one sampled commit.
+#[derive(Debug, Serialize)]
+pub struct TimelinePoint {
+    /// Start of the period this sample represents.
+    pub date: NaiveDate,
+    /// Id of the sampled commit, the last one in its period.
+    pub commit: String,
+    /// Lines of code in the commit's tree.
+    pub stats: ProjectStats,
+}
+
+/// Classification results for blobs, keyed by blob id and language.
+type BlobCache = HashMap<(Oid, &'static str), Option<FileStats>>;
+
+/// A line added or deleted in a diff.
+struct ChangedLine<'a> {
+    /// Repository-relative path of the changed file.
+    path: &'a Path,
+    /// Language of the changed file.
+    language: &'static LanguageInfo,
+    /// '+' for added lines, '-' for deleted lines.
+    origin: char,
+    /// Classification of the line within its file.
+    line_type: LineType,
+    /// Raw line content, including the line ending.
+    content: &'a [u8],
+}
+
+/// Files changed by a tree-to-tree diff.
+#[derive(Debug, Default)]
+struct DiffFiles {
+    /// Number of changed files that passed the path filter.
+    changed: usize,
+    /// Number of those files that were renamed.
+    renamed: usize,
+    /// Number of those files that were copied.
+    copied: usize,
+    /// Old and new paths of the renamed files.
+    renames: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Line changes accumulated for a single commit.
+#[derive(Debug, Default)]
+struct CommitChanges {
+    additions: FileStats,
+    deletions: FileStats,
+    by_language: HashMap<&'static str, LineChanges>,
+    by_file: HashMap<PathBuf, LineChanges>,
+    files: DiffFiles,
+}
+
+impl CommitChanges {
+    /// Record `count` occurrences of a changed line.
+    fn record(&mut self, line: &ChangedLine, count: usize) {
+        let language = self.by_language.entry(line.language.name).or_default();
+        let file = self.by_file.entry(line.path.to_path_buf()).or_default();
+        let (total, per_language, per_file) = match line.origin {
+            '+' => (
+                &mut self.additions,
+                &mut language.additions,
+                &mut file.additions,
+            ),
+            '-' => (
+                &mut self.deletions,
+                &mut language.deletions,
+                &mut file.deletions,
+            ),
+            _ => return,
+        };
+
+        for target in [total, per_language, per_file] {
+            match line.line_type {
+                LineType::Blank => target.blank += count,
+                LineType::Comment => target.comment += count,
+                LineType::Doc => target.doc += count,
+                LineType::Code => target.code += count,
+                LineType::Mixed => target.mixed += count,
+            }
+        }
+    }
+
+    /// Fold mixed lines into the counts a policy asks for.
+    fn apply_mixed_lines(&mut self, policy: MixedLines) {
+        let per_language = self.by_language.values_mut();
+        let per_file = self.by_file.values_mut();
+        for changes in per_language.chain(per_file) {
+            changes.additions = policy.apply(changes.additions);
+            changes.deletions = policy.apply(changes.deletions);
+        }
+        self.additions = policy.apply(self.additions);
+        self.deletions = policy.apply(self.deletions);
+    }
+}
+
+/// Consecutive commits before `since` after which a history walk stops, as in git.
+const SINCE_SLOP: usize = 5;
+
+/// Decides when a newest-first walk is past `since` for good.
+///
+/// Commits are walked newest first by committer time, but clock skew can make
+/// a commit older than its own descendants, and an incremental walk like git's
+/// then reaches it before newer ancestors. So a single old commit doesn't end
+/// the walk; like git, it only stops after a run of [`SINCE_SLOP`] of them.
+///
+/// Wall-clock times can differ from the walk order by up to a day across time
+/// zones, so commits only count as old a day before `since`. Only committer
+/// times can end a walk: author dates may be later than committer dates in
+/// imported or rewritten history, so a commit authored in range can come after
+/// any number of old ones. Walks filtered by author date never stop early; see
+/// [`GitAnalyzer::since_cutoff`].
+struct SinceCutoff {
+    since: Option<NaiveDateTime>,
+    old_run: usize,
+}
+
+impl SinceCutoff {
+    fn new(since: Option<NaiveDateTime>) -> Self {
+        Self { since, old_run: 0 }
+    }
+
+    /// Record the committer time of the next walked commit and check if the walk can stop.
+    fn is_done(&mut self, time: git2::Time) -> bool {
+        let Some(since) = self.since else {
+            return false;
+        };
+
+        let utc = DateTime::from_timestamp(time.seconds(), 0).unwrap_or(DateTime::UNIX_EPOCH);
+        if utc.naive_utc() < since - chrono::Duration::days(1) {
+            self.old_run += 1;
+        } else {
+            self.old_run = 0;
+        }
+
+        self.old_run >= SINCE_SLOP
+    }
+}
+
+/// Default similarity (in percent) for a file to be considered renamed or copied, as in git.
+pub const DEFAULT_RENAME_THRESHOLD: u16 = 50;
+
 /// Git repository analyzer.
 pub struct GitAnalyzer {
     repo: Repository,
+    detector: LanguageDetector,
+    paths: Vec<PathBuf>,
+    exclude: Vec<String>,
+    include: Vec<String>,
+    authors: AuthorFilter,
+    group_by_email: bool,
+    co_author_credit: CoAuthorCredit,
+    mixed_lines: MixedLines,
+    merge_policy: MergePolicy,
+    rename_threshold: Option<u16>,
+    revision: Option<String>,
+    all_branches: bool,
+    hidden: bool,
+    timezone: Timezone,
+    date_source: DateSource,
+}
+
+/// Restricts history analysis to a set of repository paths and glob patterns.
+///
+/// Mirrors the filtering applied by [`crate::walker::DirectoryWalker`] in snapshot mode.
+struct PathFilter {
+    /// Repository-relative path prefixes (empty means the whole repository).
+    prefixes: Vec<PathBuf>,
+    /// Include/exclude glob overrides.
+    overrides: Override,
+    /// Working tree root that repository-relative paths are joined to.
+    root: PathBuf,
+}
+
+impl PathFilter {
+    /// Check if this filter restricts anything beyond the built-in skip list.
+    fn is_restricted(&self) -> bool {
+        !self.prefixes.is_empty() || !self.overrides.is_empty()
+    }
+
+    /// Check if a repository-relative file path passes the filter.
+    fn matches(&self, path: &Path) -> bool {
+        if should_skip_file(path) {
+            return false;
+        }
+
+        if !self.prefixes.is_empty() && !self.prefixes.iter().any(|p| path.starts_with(p)) {
+            return false;
+        }
+
+        // Excluded parent directories exclude everything below them, like the walker
+        let full_path = self.root.join(path);
+        let excluded_dir = full_path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root) && *dir != self.root)
+            .any(|dir| self.overrides.matched(dir, true).is_ignore());
+
+        !excluded_dir && !self.overrides.matched(&full_path, false).is_ignore()
+    }
 }
 
 impl GitAnalyzer {
@@ -81,7 +681,255 @@ impl GitAnalyzer {
     /// Returns None if the path is not in a git repository.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, git2::Error> {
         let repo = Repository::discover(path)?;
-        Ok(Self { repo })
+        Ok(Self {
+            repo,
+            detector: LanguageDetector::new(),
+            paths: Vec::new(),
+            exclude: Vec::new(),
+            include: Vec::new(),
+            authors: AuthorFilter::default(),
+            group_by_email: false,
+            co_author_credit: CoAuthorCredit::default(),
+            mixed_lines: MixedLines::default(),
+            merge_policy: MergePolicy::default(),
+            rename_threshold: Some(DEFAULT_RENAME_THRESHOLD),
+            revision: None,
+            all_branches: false,
+            hidden: false,
+            timezone: Timezone::default(),
+            date_source: DateSource::default(),
+        })
+    }
+
+    /// Only analyze commits whose author passes this filter.
+    pub fn authors(mut self, filter: AuthorFilter) -> Self {
+        self.authors = filter;
+        self
+    }
+
+    /// Restrict history analysis to changes under these paths.
+    ///
+    /// Paths are resolved against the current directory and must be inside
+    /// the repository's working tree.
+    pub fn paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Set exclude patterns (glob patterns to exclude).
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.exclude = patterns;
+        self
+    }
+
+    /// Set include patterns (glob patterns to include, overrides excludes).
+    pub fn include(mut self, patterns: Vec<String>) -> Self {
+        self.include = patterns;
+        self
+    }
+
+    /// Group author statistics by email address instead of display name.
+    pub fn group_by_email(mut self, group_by_email: bool) -> Self {
+        self.group_by_email = group_by_email;
+        self
+    }
+
+    /// Set how co-authors from `Co-authored-by:` trailers are credited.
+    pub fn co_author_credit(mut self, credit: CoAuthorCredit) -> Self {
+        self.co_author_credit = credit;
+        self
+    }
+
+    /// Set how lines with both code and comments are counted.
+    pub fn mixed_lines(mut self, policy: MixedLines) -> Self {
+        self.mixed_lines = policy;
+        self
+    }
+
+    /// Set how merge commits are handled.
+    pub fn merge_policy(mut self, policy: MergePolicy) -> Self {
+        self.merge_policy = policy;
+        self
+    }
+
+    /// Set the similarity threshold (0-100) for rename and copy detection.
+    ///
+    /// Renamed and copied files only count their modified lines instead of a full
+    /// deletion plus addition. `None` disables detection.
+    pub fn rename_threshold(mut self, threshold: Option<u16>) -> Self {
+        self.rename_threshold = threshold;
+        self
+    }
+
+    /// Analyze the commits of a revision or range instead of HEAD.
+    ///
+    /// Accepts anything `git rev-parse` does, such as `main`, `origin/feature`,
+    /// `v1.0..v2.0` or `main...feature`.
+    pub fn revision(mut self, revision: Option<String>) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// Walk every local and remote-tracking branch instead of only HEAD.
+    ///
+    /// Commits reachable from several branches are only counted once.
+    pub fn all_branches(mut self, all_branches: bool) -> Self {
+        self.all_branches = all_branches;
+        self
+    }
+
+    /// Include hidden files and directories in snapshots.
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Bucket and filter commits by wall-clock time in this time zone.
+    pub fn timezone(mut self, timezone: Timezone) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Bucket and filter commits by their author or committer date.
+    pub fn date_source(mut self, date_source: DateSource) -> Self {
+        self.date_source = date_source;
+        self
+    }
+
+    /// Get the wall-clock time a commit is bucketed and filtered by.
+    fn commit_time(&self, commit: &git2::Commit) -> NaiveDateTime {
+        let time = match self.date_source {
+            DateSource::Committer => commit.time(),
+            DateSource::Author => commit.author().when(),
+        };
+        self.timezone.commit_datetime(time)
+    }
+
+    /// Get the cutoff that ends a newest-first walk past `since`.
+    ///
+    /// With author dates, no commit is ever known to be the last one in range,
+    /// so the whole history is walked and old commits are only skipped.
+    fn since_cutoff(&self, since: Option<NaiveDateTime>) -> SinceCutoff {
+        match self.date_source {
+            DateSource::Committer => SinceCutoff::new(since),
+            DateSource::Author => SinceCutoff::new(None),
+        }
+    }
+
+    /// Push the configured starting points onto a revision walk.
+    fn push_revisions(&self, revwalk: &mut Revwalk) -> Result<(), git2::Error> {
+        if self.all_branches {
+            revwalk.push_glob("refs/heads")?;
+            revwalk.push_glob("refs/remotes")?;
+        }
+
+        let Some(spec) = &self.revision else {
+            if !self.all_branches {
+                revwalk.push_head()?;
+            }
+            return Ok(());
+        };
+
+        let revspec = self.repo.revparse(spec)?;
+        if revspec.mode().contains(RevparseMode::SINGLE) {
+            if let Some(from) = revspec.from() {
+                revwalk.push(from.peel_to_commit()?.id())?;
+            }
+            return Ok(());
+        }
+
+        // A range: walk what's reachable from the end but not from the start
+        let from = match revspec.from() {
+            Some(from) => Some(from.peel_to_commit()?.id()),
+            None => None,
+        };
+        let to = match revspec.to() {
+            Some(to) => to.peel_to_commit()?.id(),
+            None => self.repo.head()?.peel_to_commit()?.id(),
+        };
+        revwalk.push(to)?;
+
+        if let Some(from) = from {
+            if revspec.mode().contains(RevparseMode::MERGE_BASE) {
+                // Symmetric difference (a...b): both sides, down to their merge base
+                revwalk.push(from)?;
+                revwalk.hide(self.repo.merge_base(from, to)?)?;
+            } else {
+                revwalk.hide(from)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the key an author's statistics are grouped under.
+    ///
+    /// Returns None if the signature has no (valid UTF-8) name or email.
+    fn author_key(&self, author: &Signature) -> Option<String> {
+        if self.group_by_email {
+            author.email().map(|email| email.to_lowercase())
+        } else {
+            author.name().map(|name| name.to_string())
+        }
+    }
+
+    /// Build the path filter from the configured paths and patterns.
+    fn path_filter(&self) -> Result<PathFilter, git2::Error> {
+        let Some(workdir) = self.repo.workdir() else {
+            // Bare repository: there is no working tree to resolve paths against
+            return Ok(PathFilter {
+                prefixes: Vec::new(),
+                overrides: Override::empty(),
+                root: PathBuf::new(),
+            });
+        };
+        let workdir = workdir
+            .canonicalize()
+            .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+
+        let mut prefixes = Vec::new();
+        for path in &self.paths {
+            let full_path = path
+                .canonicalize()
+                .map_err(|e| git2::Error::from_str(&format!("{}: {}", path.display(), e)))?;
+            let relative = full_path.strip_prefix(&workdir).map_err(|_| {
+                git2::Error::from_str(&format!(
+                    "{} is outside the repository at {}",
+                    path.display(),
+                    workdir.display()
+                ))
+            })?;
+            prefixes.push(relative.to_path_buf());
+        }
+
+        // The repository root itself covers everything
+        if prefixes.iter().any(|p| p.as_os_str().is_empty()) {
+            prefixes.clear();
+        }
+
+        // Patterns are relative to the first path, like in snapshot mode
+        let root = prefixes
+            .first()
+            .map(|p| workdir.join(p))
+            .filter(|p| p.is_dir())
+            .unwrap_or_else(|| workdir.clone());
+
+        let mut override_builder = OverrideBuilder::new(&root);
+        for pattern in &self.exclude {
+            let _ = override_builder.add(&format!("!{}", pattern));
+        }
+        for pattern in &self.include {
+            let _ = override_builder.add(pattern);
+        }
+        let overrides = override_builder
+            .build()
+            .unwrap_or_else(|_| Override::empty());
+
+        Ok(PathFilter {
+            prefixes,
+            overrides,
+            root: workdir,
+        })
     }
 
     /// Check if a path is in a git repository.
@@ -91,35 +939,47 @@ impl GitAnalyzer {
 
     /// Analyze commit history and return historical statistics.
     ///
+    /// `since` and `until` are wall-clock times in the analyzer's
+    /// [`timezone`](Self::timezone); `until` includes the whole day after it.
     /// If `verbose` is true, progress will be printed to stderr every 100 commits.
     pub fn analyze_history(
         &self,
-        since: Option<DateTime<Utc>>,
-        until: Option<DateTime<Utc>>,
+        since: Option<NaiveDateTime>,
+        until: Option<NaiveDateTime>,
         verbose: bool,
     ) -> Result<HistoricalStats, git2::Error> {
         let mut stats = HistoricalStats::default();
         let mut daily_map: HashMap<NaiveDate, DailyStats> = HashMap::new();
+        // Older paths of renamed files, mapped to their latest path
+        let mut renamed_to: HashMap<PathBuf, PathBuf> = HashMap::new();
         let mut commits_processed = 0;
+        let filter = self.path_filter()?;
+
+        // Resolve author identities through .mailmap (empty if there is none)
+        let mailmap = self.repo.mailmap()?;
 
         // Walk commits
         let mut revwalk = self.repo.revwalk()?;
-        revwalk.push_head()?;
+        self.push_revisions(&mut revwalk)?;
         revwalk.set_sorting(git2::Sort::TIME)?;
+        if self.merge_policy == MergePolicy::FirstParent {
+            revwalk.simplify_first_parent()?;
+        }
 
+        let mut cutoff = self.since_cutoff(since);
         for oid in revwalk {
             let oid = oid?;
             let commit = self.repo.find_commit(oid)?;
 
             // Filter by date if specified
-            let commit_time = DateTime::from_timestamp(commit.time().seconds(), 0)
-                .unwrap_or(DateTime::UNIX_EPOCH);
+            let commit_time = self.commit_time(&commit);
 
             // Check if commit is before 'since' date
-            if let Some(since_date) = since {
-                if commit_time < since_date {
-                    break; // Stop processing older commits
-                }
+            if cutoff.is_done(commit.time()) {
+                break; // Stop processing older commits
+            }
+            if since.is_some_and(|since_date| commit_time < since_date) {
+                continue;
             }
 
             // Check if commit is after 'until' date (inclusive - we want commits on the until date)
@@ -131,7 +991,24 @@ impl GitAnalyzer {
                 }
             }
 
-            stats.total_commits += 1;
+            // Skip commits by other authors (a matching co-author is enough)
+            let author = commit.author_with_mailmap(&mailmap)?;
+            let co_authors = if self.co_author_credit == CoAuthorCredit::None {
+                Vec::new()
+            } else {
+                Self::co_authors(&commit, &mailmap)
+            };
+            if !self.matches_author(&author) && !co_authors.iter().any(|c| self.matches_author(c)) {
+                continue;
+            }
+
+            if commit.parent_count() > 1 && self.merge_policy == MergePolicy::Skip {
+                if !filter.is_restricted() || self.merge_touches(&commit, &filter)? {
+                    stats.merges_skipped += 1;
+                }
+                continue;
+            }
+
             commits_processed += 1;
 
             // Show progress every 100 commits if verbose
@@ -139,38 +1016,56 @@ impl GitAnalyzer {
                 eprintln!("Processed {} commits...", commits_processed);
             }
 
-            // Get commit date
-            let date = commit_time.date_naive();
+            // Analyze commit diff, skipping commits outside the requested paths
+            let Some(changes) = self.analyze_commit(&commit, &filter)? else {
+                continue;
+            };
+            let (additions, deletions) = (changes.additions, changes.deletions);
+            let by_language = changes.by_language;
+            self.record_files(
+                &mut stats.by_file,
+                &mut renamed_to,
+                &author,
+                &co_authors,
+                changes.by_file,
+                changes.files.renames,
+            );
+            stats.renames += changes.files.renamed;
+            stats.copies += changes.files.copied;
+            stats.total_commits += 1;
 
-            // Analyze commit diff
-            let (additions, deletions) = self.analyze_commit(&commit)?;
+            // Get commit date
+            let date = commit_time.date();
 
             // Update daily stats
             let daily_stat = daily_map.entry(date).or_insert_with(|| DailyStats {
                 date,
-                additions: FileStats::default(),
-                deletions: FileStats::default(),
-                net_code: 0,
+                ..DailyStats::default()
             });
 
             daily_stat.additions += additions;
             daily_stat.deletions += deletions;
             daily_stat.net_code += (additions.code as i64) - (deletions.code as i64);
-
-            // Track by author - extract name to owned String to avoid lifetime issues
-            let author_name = commit.author().name().map(|s| s.to_string());
-            if let Some(author) = author_name {
-                let author_stats = stats
-                    .by_author
-                    .entry(author)
-                    .or_insert_with(FileStats::default);
-                *author_stats += additions;
+            for (language, language_changes) in by_language {
+                *daily_stat
+                    .by_language
+                    .entry(language.to_string())
+                    .or_default() += language_changes;
             }
+
+            // Track by author
+            self.credit_authors(
+                &mut stats.by_author,
+                date,
+                &author,
+                &co_authors,
+                (additions, deletions),
+            );
         }
 
         // Convert daily map to sorted vec
         let mut daily: Vec<_> = daily_map.into_values().collect();
-        daily.sort_by(|a, b| b.date.cmp(&a.date)); // Most recent first
+        daily.sort_by_key(|d| std::cmp::Reverse(d.date)); // Most recent first
 
         stats.daily = daily;
 
@@ -182,143 +1077,1988 @@ impl GitAnalyzer {
         Ok(stats)
     }
 
-    /// Analyze a single commit and return added/deleted line stats.
-    fn analyze_commit(&self, commit: &git2::Commit) -> Result<(FileStats, FileStats), git2::Error> {
-        let mut additions = FileStats::default();
-        let mut deletions = FileStats::default();
-
-        // Get the tree for this commit
-        let tree = commit.tree()?;
+    /// Count lines of code in the tree of a revision, without checking it out.
+    ///
+    /// Blobs are read straight from the object database and filtered like files
+    /// in a directory walk: the built-in skip list, paths, include/exclude
+    /// patterns and hidden files all apply.
+    pub fn snapshot(&self, revision: &str) -> Result<ProjectStats, git2::Error> {
+        let filter = self.path_filter()?;
+        let tree = self.repo.revparse_single(revision)?.peel_to_tree()?;
+        self.snapshot_tree(&tree, &filter, &mut BlobCache::new())
+    }
 
-        // Get parent tree (if exists)
-        let parent_tree = if commit.parent_count() > 0 {
-            Some(commit.parent(0)?.tree()?)
-        } else {
-            None
+    /// Attribute the code lines of working tree files to their authors with git blame.
+    ///
+    /// Each file is classified as it is on disk, and only lines with code
+    /// count, including those that also have comments.
+    /// Lines changed since the last commit, and files that were never
+    /// committed, count as uncommitted. Files outside the repository or in an
+    /// unknown language are skipped. If `verbose` is true, progress will be
+    /// printed to stderr every 100 files.
+    pub fn ownership(
+        &self,
+        files: &[PathBuf],
+        verbose: bool,
+    ) -> Result<OwnershipStats, git2::Error> {
+        let Some(workdir) = self.repo.workdir() else {
+            return Err(git2::Error::from_str(
+                "ownership requires a repository with a working tree",
+            ));
         };
+        let workdir = workdir
+            .canonicalize()
+            .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+        let mailmap = self.repo.mailmap()?;
+        let mut authors = HashMap::new();
 
-        // Create diff
-        let diff = if let Some(parent_tree) = parent_tree {
-            self.repo
-                .diff_tree_to_tree(Some(&parent_tree), Some(&tree), None)?
+        let mut stats = OwnershipStats::default();
+        for (index, file) in files.iter().enumerate() {
+            if verbose && index > 0 && index % 100 == 0 {
+                eprintln!("Blamed {} files...", index);
+            }
+
+            let Some(relative) = file
+                .canonicalize()
+                .ok()
+                .and_then(|path| path.strip_prefix(&workdir).ok().map(Path::to_path_buf))
+            else {
+                continue;
+            };
+            let Some(language) = self.detector.detect_from_path(&relative) else {
+                continue;
+            };
+            let Ok(content) = std::fs::read(file) else {
+                continue;
+            };
+            if content.iter().take(8192).any(|&b| b == 0) {
+                continue; // Binary file
+            }
+
+            let text = String::from_utf8_lossy(&content);
+            let lines: Vec<&str> = text.lines().collect();
+            let types = classify_lines(&lines, language);
+            let ownership =
+                self.blame_ownership(&relative, &content, &types, &mailmap, &mut authors)?;
+            stats
+                .files
+                .insert(relative.to_string_lossy().replace('\\', "/"), ownership);
+        }
+
+        if verbose {
+            eprintln!("Completed blaming {} files", stats.files.len());
+        }
+
+        Ok(stats)
+    }
+
+    /// Blame a file's current content and count the code lines of each author.
+    ///
+    /// `authors` caches the author key of each commit seen so far.
+    fn blame_ownership(
+        &self,
+        path: &Path,
+        content: &[u8],
+        types: &[LineType],
+        mailmap: &Mailmap,
+        authors: &mut HashMap<Oid, Option<String>>,
+    ) -> Result<Ownership, git2::Error> {
+        let mut ownership = Ownership::default();
+        let code_lines = |start: usize, count: usize| {
+            types
+                .iter()
+                .skip(start)
+                .take(count)
+                .filter(|t| matches!(t, LineType::Code | LineType::Mixed))
+                .count()
+        };
+
+        // Files that were never committed have nothing to blame yet
+        let committed = if self.repo.head().is_ok() {
+            match self.repo.blame_file(path, None) {
+                Ok(blame) => Some(blame),
+                Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+                Err(e) => return Err(e),
+            }
         } else {
-            // First commit - diff against empty tree
-            self.repo.diff_tree_to_tree(None, Some(&tree), None)?
+            None
         };
+        let Some(committed) = committed else {
+            ownership.add_uncommitted(code_lines(0, types.len()));
+            return Ok(ownership);
+        };
+        let blame = committed.blame_buffer(content)?;
 
-        // Process diff
-        diff.foreach(
-            &mut |_delta, _progress| {
-                // Continue processing all files
-                true
-            },
-            None,
-            None,
-            Some(&mut |_delta, _hunk, line| {
-                let line_type = Self::classify_diff_line(line.content());
-
-                match line.origin() {
-                    '+' => {
-                        // Added line
-                        match line_type {
-                            LineType::Blank => additions.blank += 1,
-                            LineType::Comment => additions.comment += 1,
-                            LineType::Code => additions.code += 1,
-                        }
-                    }
-                    '-' => {
-                        // Deleted line
-                        match line_type {
-                            LineType::Blank => deletions.blank += 1,
-                            LineType::Comment => deletions.comment += 1,
-                            LineType::Code => deletions.code += 1,
+        for hunk in blame.iter() {
+            let lines = code_lines(hunk.final_start_line() - 1, hunk.lines_in_hunk());
+            if lines == 0 {
+                continue;
+            }
+
+            // Lines only in the buffer have no commit
+            if hunk.final_commit_id().is_zero() {
+                ownership.add_uncommitted(lines);
+                continue;
+            }
+
+            // Hunks split by blame_buffer lose their signature, so go through the commit
+            let oid = hunk.final_commit_id();
+            let author = match authors.get(&oid) {
+                Some(author) => author.clone(),
+                None => {
+                    let commit = self.repo.find_commit(oid)?;
+                    let author = self.author_key(&commit.author_with_mailmap(mailmap)?);
+                    authors.insert(oid, author.clone());
+                    author
+                }
+            };
+            match author {
+                Some(author) => ownership.add_lines(&author, lines),
+                None => ownership.add_uncommitted(lines),
+            }
+        }
+
+        Ok(ownership)
+    }
+
+    /// Sample the size of the codebase over first-parent history.
+    ///
+    /// Takes the last commit of each period and counts its tree like
+    /// [`snapshot`](Self::snapshot). Files that didn't change between samples
+    /// are only classified once. Samples are returned most recent first.
+    ///
+    /// The walk follows committer time, so with author dates the last commit of
+    /// a period isn't necessarily the first one seen; every commit is compared
+    /// before any tree is counted.
+    pub fn timeline(
+        &self,
+        period: Period,
+        since: Option<NaiveDateTime>,
+        until: Option<NaiveDateTime>,
+        verbose: bool,
+    ) -> Result<Vec<TimelinePoint>, git2::Error> {
+        let filter = self.path_filter()?;
+        let mut cache = BlobCache::new();
+        let mut latest: BTreeMap<NaiveDate, (NaiveDateTime, Oid)> = BTreeMap::new();
+
+        let mut revwalk = self.repo.revwalk()?;
+        self.push_revisions(&mut revwalk)?;
+        revwalk.set_sorting(git2::Sort::TIME)?;
+        revwalk.simplify_first_parent()?;
+
+        let mut cutoff = self.since_cutoff(since);
+        for oid in revwalk {
+            let oid = oid?;
+            let commit = self.repo.find_commit(oid)?;
+
+            let commit_time = self.commit_time(&commit);
+
+            if cutoff.is_done(commit.time()) {
+                break;
+            }
+            if since.is_some_and(|since_date| commit_time < since_date) {
+                continue;
+            }
+
+            // Until is inclusive of the whole day, like in analyze_history
+            if let Some(until_date) = until {
+                if commit_time >= until_date + chrono::Duration::days(1) {
+                    continue;
+                }
+            }
+
+            // The latest commit of each period closes it; on equal times, the
+            // first one seen is the newest in the walk
+            let date = period.bucket_start(commit_time.date());
+            let closing = latest.entry(date).or_insert((commit_time, oid));
+            if commit_time > closing.0 {
+                *closing = (commit_time, oid);
+            }
+        }
+
+        let mut points = Vec::new();
+        for (date, (_, oid)) in latest.into_iter().rev() {
+            let commit = self.repo.find_commit(oid)?;
+            points.push(TimelinePoint {
+                date,
+                commit: oid.to_string(),
+                stats: self.snapshot_tree(&commit.tree()?, &filter, &mut cache)?,
+            });
+
+            if verbose && points.len() % 10 == 0 {
+                eprintln!("Sampled {} commits...", points.len());
+            }
+        }
+
+        Ok(points)
+    }
+
+    /// Count lines of code in a tree, reusing cached results for known blobs.
+    fn snapshot_tree(
+        &self,
+        tree: &git2::Tree,
+        filter: &PathFilter,
+        cache: &mut BlobCache,
+    ) -> Result<ProjectStats, git2::Error> {
+        let processor = FileProcessor::new().mixed_lines(self.mixed_lines);
+        let mut project_stats = ProjectStats::new();
+        let mut error = None;
+
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            let path = Path::new(root).join(entry.name().unwrap_or_default());
+
+            if entry.kind() == Some(ObjectType::Tree) {
+                let hidden = path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+                return if hidden && !self.hidden {
+                    TreeWalkResult::Skip
+                } else {
+                    TreeWalkResult::Ok
+                };
+            }
+
+            // Symlinks and submodules have no content of their own to count
+            if entry.kind() != Some(ObjectType::Blob) || entry.filemode() == 0o120000 {
+                return TreeWalkResult::Ok;
+            }
+
+            let hidden = entry.name().is_some_and(|name| name.starts_with('.'));
+            if (hidden && !self.hidden) || !filter.matches(&path) {
+                return TreeWalkResult::Ok;
+            }
+
+            // The same content can classify differently under another extension
+            let Some(language) = self.detector.detect_from_path(&path) else {
+                return TreeWalkResult::Ok;
+            };
+
+            let key = (entry.id(), language.name);
+            let stats = match cache.get(&key) {
+                Some(stats) => *stats,
+                None => {
+                    let blob = match self.repo.find_blob(entry.id()) {
+                        Ok(blob) => blob,
+                        Err(e) => {
+                            error = Some(e);
+                            return TreeWalkResult::Abort;
                         }
-                    }
-                    _ => {} // Context lines, ignore
+                    };
+                    let stats = processor
+                        .process_bytes(&path, blob.content())
+                        .map(|(_, stats)| stats);
+                    cache.insert(key, stats);
+                    stats
                 }
-                true
-            }),
-        )?;
+            };
 
-        Ok((additions, deletions))
+            if let Some(stats) = stats {
+                project_stats.add_file_stats(language.name, stats);
+            }
+
+            TreeWalkResult::Ok
+        })?;
+
+        match error {
+            Some(e) => Err(e),
+            None => Ok(project_stats),
+        }
     }
 
-    /// Classify a single line from a diff.
-    fn classify_diff_line(content: &[u8]) -> LineType {
-        // Convert to string, skip invalid UTF-8
-        let line = match std::str::from_utf8(content) {
-            Ok(s) => s,
-            Err(_) => return LineType::Code, // Treat binary as code
+    /// Check if a signature passes the author filter.
+    fn matches_author(&self, signature: &Signature) -> bool {
+        self.authors.matches(
+            signature.name().unwrap_or_default(),
+            signature.email().unwrap_or_default(),
+        )
+    }
+
+    /// Parse the co-authors listed in a commit's `Co-authored-by:` trailers.
+    fn co_authors(commit: &git2::Commit, mailmap: &Mailmap) -> Vec<Signature<'static>> {
+        let Some(message) = commit.message() else {
+            return Vec::new();
+        };
+        let Ok(trailers) = git2::message_trailers_strs(message) else {
+            return Vec::new();
         };
 
-        let trimmed = line.trim();
+        trailers
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case("co-authored-by"))
+            .filter_map(|(_, value)| Self::parse_identity(value))
+            .filter_map(|(name, email)| Signature::now(name, email).ok())
+            .map(|signature| mailmap.resolve_signature(&signature).unwrap_or(signature))
+            .collect()
+    }
 
-        // Check if blank
-        if trimmed.is_empty() {
-            return LineType::Blank;
+    /// Split a `Name <email>` identity into its name and email.
+    fn parse_identity(value: &str) -> Option<(&str, &str)> {
+        let (name, email) = value.rsplit_once('<')?;
+        let email = email.trim().strip_suffix('>')?.trim();
+        let name = name.trim();
+        if name.is_empty() || email.is_empty() {
+            return None;
         }
+        Some((name, email))
+    }
 
-        // Simple heuristics for comments (not perfect, but reasonable)
-        // This is a simplified version - doesn't track multi-line comment state
-        if trimmed.starts_with("//")
-            || trimmed.starts_with('#')
-            || trimmed.starts_with("--")
-            || trimmed.starts_with("/*")
-            || trimmed.starts_with("*/")
-            || trimmed.starts_with('*')
-            || trimmed.starts_with("<!--")
-            || trimmed.starts_with("-->")
-        {
-            return LineType::Comment;
+    /// Credit a commit's changes to its author and co-authors.
+    ///
+    /// Only identities passing the author filter are recorded.
+    fn credit_authors(
+        &self,
+        by_author: &mut HashMap<String, AuthorStats>,
+        date: NaiveDate,
+        author: &Signature,
+        co_authors: &[Signature],
+        (additions, deletions): (FileStats, FileStats),
+    ) {
+        let author_key = self.author_key(author);
+
+        // Co-authors that resolve to the commit author or repeat are credited once
+        let mut co_author_keys: Vec<(String, bool)> = Vec::new();
+        for co_author in co_authors {
+            let Some(key) = self.author_key(co_author) else {
+                continue;
+            };
+            if Some(&key) != author_key.as_ref() && !co_author_keys.iter().any(|(k, _)| *k == key) {
+                co_author_keys.push((key, self.matches_author(co_author)));
+            }
+        }
+
+        let (author_additions, co_author_additions) =
+            self.co_author_credit.share(additions, co_author_keys.len());
+        let (author_deletions, co_author_deletions) =
+            self.co_author_credit.share(deletions, co_author_keys.len());
+
+        if let Some(key) = author_key {
+            if self.matches_author(author) {
+                by_author.entry(key).or_default().record_commit(
+                    date,
+                    author_additions,
+                    author_deletions,
+                );
+            }
+        }
+
+        for (key, matches) in co_author_keys {
+            if matches {
+                by_author.entry(key).or_default().record_co_authored(
+                    date,
+                    co_author_additions,
+                    co_author_deletions,
+                );
+            }
+        }
+    }
+
+    /// Record a commit's per-file changes under each file's latest path.
+    ///
+    /// History is walked newest first, so renames seen so far tell where the
+    /// files of older commits ended up.
+    fn record_files(
+        &self,
+        by_file: &mut HashMap<String, FileChurn>,
+        renamed_to: &mut HashMap<PathBuf, PathBuf>,
+        author: &Signature,
+        co_authors: &[Signature],
+        changes: HashMap<PathBuf, LineChanges>,
+        renames: Vec<(PathBuf, PathBuf)>,
+    ) {
+        let mut authors: Vec<String> = Vec::new();
+        for signature in std::iter::once(author).chain(co_authors) {
+            if let Some(key) = self.author_key(signature) {
+                authors.push(key);
+            }
+        }
+
+        let latest = |renamed_to: &HashMap<PathBuf, PathBuf>, path: PathBuf| {
+            renamed_to.get(&path).cloned().unwrap_or(path)
+        };
+
+        for (path, file_changes) in changes {
+            let path = latest(renamed_to, path);
+            by_file
+                .entry(path.to_string_lossy().replace('\\', "/"))
+                .or_default()
+                .record_commit(file_changes, &authors);
+        }
+
+        for (old_path, new_path) in renames {
+            let new_path = latest(renamed_to, new_path);
+            renamed_to.insert(old_path, new_path);
+        }
+    }
+
+    /// Measure the current size of every file in the history's hotspots.
+    ///
+    /// Each file's working tree copy is classified with [`FileProcessor`] to
+    /// fill in [`FileChurn::current_code`]; files that were deleted stay None.
+    pub fn measure_hotspots(&self, stats: &mut HistoricalStats) {
+        let Some(workdir) = self.repo.workdir() else {
+            return;
+        };
+
+        let processor = FileProcessor::new().mixed_lines(self.mixed_lines);
+        for (path, churn) in stats.by_file.iter_mut() {
+            churn.current_code = processor
+                .process_file(&workdir.join(path))
+                .map(|(_, file_stats)| file_stats.code);
+        }
+    }
+
+    /// Analyze a single commit and return its line changes.
+    ///
+    /// Merge commits are diffed against every parent when the merge policy
+    /// asks for it, and against the first parent otherwise.
+    ///
+    /// Returns None if the filter restricts the analysis and the commit
+    /// doesn't change any matching file.
+    fn analyze_commit(
+        &self,
+        commit: &git2::Commit,
+        filter: &PathFilter,
+    ) -> Result<Option<CommitChanges>, git2::Error> {
+        let mut changes = CommitChanges::default();
+        let tree = commit.tree()?;
+
+        if commit.parent_count() > 1 && self.merge_policy == MergePolicy::AllParents {
+            self.analyze_merge(commit, &tree, filter, &mut changes)?;
+        } else {
+            // Get parent tree (the first commit is diffed against the empty tree)
+            let parent_tree = if commit.parent_count() > 0 {
+                Some(commit.parent(0)?.tree()?)
+            } else {
+                None
+            };
+
+            changes.files = self.diff_trees(parent_tree.as_ref(), &tree, filter, &mut |line| {
+                changes.record(&line, 1)
+            })?;
+        }
+
+        if filter.is_restricted() && changes.files.changed == 0 {
+            return Ok(None);
+        }
+
+        changes.apply_mixed_lines(self.mixed_lines);
+        Ok(Some(changes))
+    }
+
+    /// Check if a merge commit changed any file that passes the filter.
+    ///
+    /// Like `git log -- <path>`, a merge only counts if its tree differs from
+    /// every parent's within the filter, i.e. it didn't just take one side.
+    fn merge_touches(
+        &self,
+        commit: &git2::Commit,
+        filter: &PathFilter,
+    ) -> Result<bool, git2::Error> {
+        let tree = commit.tree()?;
+        for parent in commit.parents() {
+            let mut diff_options = DiffOptions::new();
+            for prefix in &filter.prefixes {
+                diff_options.pathspec(prefix);
+            }
+            let diff = self.repo.diff_tree_to_tree(
+                Some(&parent.tree()?),
+                Some(&tree),
+                Some(&mut diff_options),
+            )?;
+            let touched = diff.deltas().any(|delta| {
+                [delta.old_file().path(), delta.new_file().path()]
+                    .into_iter()
+                    .flatten()
+                    .any(|path| filter.matches(path))
+            });
+            if !touched {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Record the lines a merge commit changed relative to all of its parents.
+    ///
+    /// A line only counts if it was added (or deleted) compared to every parent,
+    /// so changes that merely come from one side of the merge are ignored and
+    /// what remains are the merge's own edits, such as conflict resolutions.
+    /// Renames and copies are attributed to the side they come from, so they
+    /// aren't counted for the merge.
+    fn analyze_merge(
+        &self,
+        commit: &git2::Commit,
+        tree: &git2::Tree,
+        filter: &PathFilter,
+        changes: &mut CommitChanges,
+    ) -> Result<(), git2::Error> {
+        type LineKey = (PathBuf, char, LineType, Vec<u8>);
+
+        let mut common: Option<HashMap<LineKey, (usize, &'static LanguageInfo)>> = None;
+        for parent in commit.parents() {
+            let parent_tree = parent.tree()?;
+            let mut lines: HashMap<LineKey, (usize, &'static LanguageInfo)> = HashMap::new();
+            self.diff_trees(Some(&parent_tree), tree, filter, &mut |line| {
+                let key = (
+                    line.path.to_path_buf(),
+                    line.origin,
+                    line.line_type,
+                    line.content.to_vec(),
+                );
+                lines.entry(key).or_insert((0, line.language)).0 += 1;
+            })?;
+
+            // Keep the lines changed relative to every parent seen so far
+            common = Some(match common {
+                None => lines,
+                Some(mut common) => {
+                    common.retain(|key, (count, _)| match lines.get(key) {
+                        Some((other, _)) => {
+                            *count = (*count).min(*other);
+                            true
+                        }
+                        None => false,
+                    });
+                    common
+                }
+            });
+        }
+
+        let mut files = HashSet::new();
+        for ((path, origin, line_type, content), (count, language)) in common.unwrap_or_default() {
+            let line = ChangedLine {
+                path: &path,
+                language,
+                origin,
+                line_type,
+                content: &content,
+            };
+            changes.record(&line, count);
+            files.insert(path);
+        }
+        changes.files.changed = files.len();
+
+        Ok(())
+    }
+
+    /// Diff two trees and report every added or deleted line of recognized text files.
+    ///
+    /// Returns counts of the changed files that pass the filter. A file renamed
+    /// or copied across the filter boundary counts if either side passes it.
+    fn diff_trees(
+        &self,
+        old_tree: Option<&git2::Tree>,
+        new_tree: &git2::Tree,
+        filter: &PathFilter,
+        on_line: &mut dyn FnMut(ChangedLine),
+    ) -> Result<DiffFiles, git2::Error> {
+        let mut files = DiffFiles::default();
+
+        // Let git prune the tree walk to the requested paths, unless renames
+        // are detected, which needs the files on the other side of the filter
+        let mut diff_options = DiffOptions::new();
+        if self.rename_threshold.is_none() {
+            for prefix in &filter.prefixes {
+                diff_options.pathspec(prefix);
+            }
+        }
+
+        let mut diff =
+            self.repo
+                .diff_tree_to_tree(old_tree, Some(new_tree), Some(&mut diff_options))?;
+
+        // Pair up deleted and added files that are renames or copies of each other
+        if let Some(threshold) = self.rename_threshold {
+            let mut find_options = DiffFindOptions::new();
+            find_options
+                .renames(true)
+                .copies(true)
+                .rename_threshold(threshold)
+                .copy_threshold(threshold);
+            diff.find_similar(Some(&mut find_options))?;
+        }
+
+        // Process each changed file with the classifier of its language
+        for idx in 0..diff.deltas().len() {
+            let Some(patch) = Patch::from_diff(&diff, idx)? else {
+                continue;
+            };
+
+            let delta = patch.delta();
+            let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) else {
+                continue;
+            };
+            if !filter.matches(path) && !delta.old_file().path().is_some_and(|p| filter.matches(p))
+            {
+                continue;
+            }
+            files.changed += 1;
+            match delta.status() {
+                Delta::Renamed => {
+                    files.renamed += 1;
+                    if let Some(old_path) = delta.old_file().path() {
+                        files
+                            .renames
+                            .push((old_path.to_path_buf(), path.to_path_buf()));
+                    }
+                }
+                Delta::Copied => files.copied += 1,
+                _ => {}
+            }
+
+            if delta.flags().is_binary() {
+                continue;
+            }
+
+            // Unrecognized files are skipped, just like in snapshot mode
+            let Some(language) = self.detector.detect_from_path(path) else {
+                continue;
+            };
+
+            // Classify both sides of the change in full, so that lines inside
+            // multi-line comments are recognized even when the hunk doesn't
+            // include the comment delimiters.
+            let Some(old_types) = self.classify_blob(delta.old_file().id(), language)? else {
+                continue;
+            };
+            let Some(new_types) = self.classify_blob(delta.new_file().id(), language)? else {
+                continue;
+            };
+
+            for hunk_idx in 0..patch.num_hunks() {
+                for line_idx in 0..patch.num_lines_in_hunk(hunk_idx)? {
+                    let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                    let line_type = match line.origin() {
+                        '+' => Self::line_type_at(&new_types, line.new_lineno()),
+                        '-' => Self::line_type_at(&old_types, line.old_lineno()),
+                        _ => continue, // Context lines, ignore
+                    };
+
+                    on_line(ChangedLine {
+                        path,
+                        language,
+                        origin: line.origin(),
+                        line_type,
+                        content: line.content(),
+                    });
+                }
+            }
         }
 
-        LineType::Code
+        Ok(files)
+    }
+
+    /// Classify every line of a blob with the given language.
+    ///
+    /// A zero OID (the missing side of an added or deleted file) yields no lines.
+    /// Returns None if the blob is binary.
+    fn classify_blob(
+        &self,
+        oid: Oid,
+        language: &LanguageInfo,
+    ) -> Result<Option<Vec<LineType>>, git2::Error> {
+        if oid.is_zero() {
+            return Ok(Some(Vec::new()));
+        }
+
+        let blob = self.repo.find_blob(oid)?;
+        if blob.is_binary() {
+            return Ok(None);
+        }
+
+        let content = String::from_utf8_lossy(blob.content());
+        let lines: Vec<&str> = content.lines().collect();
+        Ok(Some(classify_lines(&lines, language)))
+    }
+
+    /// Look up the type of a 1-based line number in a classified blob.
+    fn line_type_at(types: &[LineType], lineno: Option<u32>) -> LineType {
+        lineno
+            .and_then(|n| types.get((n as usize).checked_sub(1)?))
+            .copied()
+            .unwrap_or(LineType::Code)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use git2::{Signature, Time};
+    use tempfile::TempDir;
 
-    #[test]
-    fn test_classify_diff_line_blank() {
-        assert_eq!(GitAnalyzer::classify_diff_line(b""), LineType::Blank);
-        assert_eq!(GitAnalyzer::classify_diff_line(b"   "), LineType::Blank);
-        assert_eq!(GitAnalyzer::classify_diff_line(b"\t\t"), LineType::Blank);
+    /// Write the given files into the work tree and commit them on HEAD.
+    fn commit_files(repo: &Repository, files: &[(&str, &str)], time: i64) -> Oid {
+        let sig = Signature::new("Test", "test@example.com", &Time::new(time, 0)).unwrap();
+        commit_files_as(repo, files, &sig)
+    }
+
+    /// Like [`commit_files`], with an explicit author and committer.
+    fn commit_files_as(repo: &Repository, files: &[(&str, &str)], sig: &Signature) -> Oid {
+        commit_files_with_message(repo, files, sig, "commit")
+    }
+
+    /// Like [`commit_files_as`], with an explicit commit message.
+    fn commit_files_with_message(
+        repo: &Repository,
+        files: &[(&str, &str)],
+        sig: &Signature,
+        message: &str,
+    ) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            let full_path = workdir.join(path);
+            std::fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+            std::fs::write(&full_path, content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), sig, sig, message, &tree, &parents)
+            .unwrap()
     }
 
     #[test]
-    fn test_classify_diff_line_comment() {
+    fn test_line_type_at() {
+        let types = [LineType::Comment, LineType::Blank];
         assert_eq!(
-            GitAnalyzer::classify_diff_line(b"// comment"),
+            GitAnalyzer::line_type_at(&types, Some(1)),
             LineType::Comment
         );
+        assert_eq!(GitAnalyzer::line_type_at(&types, Some(2)), LineType::Blank);
+        // Out of range or missing line numbers fall back to code
+        assert_eq!(GitAnalyzer::line_type_at(&types, Some(3)), LineType::Code);
+        assert_eq!(GitAnalyzer::line_type_at(&types, Some(0)), LineType::Code);
+        assert_eq!(GitAnalyzer::line_type_at(&types, None), LineType::Code);
+    }
+
+    #[test]
+    fn test_analyze_history_uses_file_language() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit_files(
+            &repo,
+            &[
+                ("main.c", "#include <stdio.h>\n// comment\nint x;\n"),
+                ("script.py", "# comment\nx = 1\n"),
+                ("notes.unknown", "# not counted\n"),
+            ],
+            1_700_000_000,
+        );
+
+        let analyzer = GitAnalyzer::new(temp_dir.path()).unwrap();
+        let stats = analyzer.analyze_history(None, None, false).unwrap();
+
+        assert_eq!(stats.total_commits, 1);
+        let additions = stats.daily[0].additions;
+        assert_eq!(additions.code, 3);
+        assert_eq!(additions.comment, 2);
+        assert_eq!(additions.blank, 0);
+    }
+
+    #[test]
+    fn test_analyze_history_mixed_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit_files(
+            &repo,
+            &[(
+                "main.rs",
+                "// comment
+let x = 1; // set x
+let y = 2;
+",
+            )],
+            1_700_000_000,
+        );
+
+        let additions = |policy| {
+            GitAnalyzer::new(temp_dir.path())
+                .unwrap()
+                .mixed_lines(policy)
+                .analyze_history(None, None, false)
+                .unwrap()
+                .daily[0]
+                .additions
+        };
+
+        let code = additions(MixedLines::Code);
+        assert_eq!((code.comment, code.code, code.mixed), (1, 2, 0));
+        let separate = additions(MixedLines::Separate);
+        assert_eq!((separate.comment, separate.code, separate.mixed), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_analyze_history_by_language() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let monday = 1_704_067_200; // 2024-01-01
+        commit_files(
+            &repo,
+            &[
+                ("main.rs", "fn main() {}\nfn run() {}\n"),
+                ("README.md", "# Title\n"),
+            ],
+            monday,
+        );
+        commit_files(&repo, &[("main.rs", "fn main() {}\n")], monday + 86_400);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(None, None, false)
+            .unwrap();
+
+        let latest = &stats.daily[0].by_language;
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest["Rust"].deletions.code, 1);
+        assert_eq!(stats.daily[1].by_language["Markdown"].additions.code, 1);
+
+        let weekly = stats.aggregate_by_week();
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].by_language["Rust"].additions.code, 2);
+        assert_eq!(weekly[0].by_language["Rust"].net_code(), 1);
+
+        let totals = stats.language_totals();
+        assert_eq!(totals[0].0, "Rust");
+        assert_eq!(totals[1].0, "Markdown");
+        assert_eq!(totals[0].1.net_code(), 1);
+    }
+
+    #[test]
+    fn test_analyze_history_tracks_multi_line_comments() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit_files(
+            &repo,
+            &[("lib.rs", "/*\n * header\n */\nfn a() {}\n")],
+            1_700_000_000,
+        );
+        // Add lines in the middle of the block comment: the hunk doesn't
+        // contain the comment delimiters
+        commit_files(
+            &repo,
+            &[(
+                "lib.rs",
+                "/*\n * header\nmore text\nand more\n */\nfn a() {}\n",
+            )],
+            1_700_100_000,
+        );
+        // Remove them again
+        commit_files(
+            &repo,
+            &[("lib.rs", "/*\n * header\n */\nfn a() {}\n")],
+            1_700_200_000,
+        );
+
+        let analyzer = GitAnalyzer::new(temp_dir.path()).unwrap();
+        let stats = analyzer.analyze_history(None, None, false).unwrap();
+
+        assert_eq!(stats.total_commits, 3);
+        // Most recent first
+        assert_eq!(stats.daily[0].deletions.comment, 2);
+        assert_eq!(stats.daily[0].deletions.code, 0);
+        assert_eq!(stats.daily[1].additions.comment, 2);
+        assert_eq!(stats.daily[1].additions.code, 0);
+    }
+
+    #[test]
+    fn test_analyze_history_path_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit_files(
+            &repo,
+            &[
+                ("services/api/main.rs", "fn main() {}\n"),
+                ("services/api/schema.generated.rs", "fn a() {}\nfn b() {}\n"),
+                ("services/web/app.js", "let x = 1;\n"),
+            ],
+            1_700_000_000,
+        );
+        commit_files(
+            &repo,
+            &[("services/web/app.js", "let x = 2;\nlet y = 3;\n")],
+            1_700_100_000,
+        );
+        commit_files(
+            &repo,
+            &[("services/api/node_modules/dep.js", "let z = 1;\n")],
+            1_700_200_000,
+        );
+
+        let analyzer = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .paths(vec![temp_dir.path().join("services/api")])
+            .exclude(vec!["*.generated.rs".to_string()]);
+        let stats = analyzer.analyze_history(None, None, false).unwrap();
+
+        // Only the first commit touches a matching file
+        assert_eq!(stats.total_commits, 1);
+        assert_eq!(stats.daily.len(), 1);
+        assert_eq!(stats.daily[0].additions.code, 1);
+    }
+
+    #[test]
+    fn test_analyze_history_outside_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let other_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit_files(&repo, &[("main.rs", "fn main() {}\n")], 1_700_000_000);
+
+        let analyzer = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .paths(vec![other_dir.path().to_path_buf()]);
+        assert!(analyzer.analyze_history(None, None, false).is_err());
+    }
+
+    #[test]
+    fn test_author_stats_record_commit() {
+        let day1 = NaiveDate::from_ymd_opt(2024, 1, 10).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2024, 1, 12).unwrap();
+        let added = FileStats {
+            blank: 1,
+            comment: 2,
+            doc: 0,
+            code: 10,
+            mixed: 0,
+        };
+        let deleted = FileStats {
+            blank: 0,
+            comment: 0,
+            doc: 0,
+            code: 4,
+            mixed: 0,
+        };
+
+        let mut author = AuthorStats::default();
+        author.record_commit(day2, added, deleted);
+        author.record_commit(day1, added, FileStats::default());
+        author.record_commit(day2, FileStats::default(), deleted);
+
+        assert_eq!(author.commits, 3);
+        assert_eq!(author.active_days, 2);
+        assert_eq!(author.additions.code, 20);
+        assert_eq!(author.deletions.code, 8);
+        assert_eq!(author.net_code, 12);
+        assert_eq!(author.first_seen, Some(day1));
+        assert_eq!(author.last_seen, Some(day2));
+    }
+
+    #[test]
+    fn test_author_filter() {
+        let filter = AuthorFilter::new(&[]).unwrap();
+        assert!(filter.is_empty());
+        assert!(filter.matches("Anyone", "anyone@example.com"));
+
+        let filter = AuthorFilter::new(&["Alice".to_string(), "@corp\\.com$".to_string()]).unwrap();
+        assert!(filter.matches("Alice Smith", "alice@home.org"));
+        assert!(filter.matches("Bob", "bob@corp.com"));
+        assert!(!filter.matches("Carol", "carol@corp.com.au"));
+
+        let error = AuthorFilter::new(&["Alice".to_string(), "(alice".to_string()]).unwrap_err();
+        assert!(error.starts_with("Invalid author pattern '(alice'"));
+    }
+
+    #[test]
+    fn test_analyze_history_author_filter() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let alice =
+            Signature::new("Alice", "alice@example.com", &Time::new(1_700_000_000, 0)).unwrap();
+        let bob = Signature::new("Bob", "bob@corp.com", &Time::new(1_700_100_000, 0)).unwrap();
+        let carol =
+            Signature::new("Carol", "carol@example.com", &Time::new(1_700_200_000, 0)).unwrap();
+        commit_files_as(&repo, &[("a.rs", "fn a() {}\n")], &alice);
+        commit_files_as(&repo, &[("b.rs", "fn b() {}\nfn c() {}\n")], &bob);
+        commit_files_as(&repo, &[("c.rs", "fn d() {}\n")], &carol);
+
+        let analyzer = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .authors(AuthorFilter::new(&["Alice".to_string(), "@corp.com".to_string()]).unwrap());
+        let stats = analyzer.analyze_history(None, None, false).unwrap();
+
+        assert_eq!(stats.total_commits, 2);
+        assert_eq!(stats.daily.len(), 2);
+        let total_added: usize = stats.daily.iter().map(|d| d.additions.code).sum();
+        assert_eq!(total_added, 3);
+        assert!(stats.by_author.contains_key("Alice"));
+        assert!(stats.by_author.contains_key("Bob"));
+        assert!(!stats.by_author.contains_key("Carol"));
+    }
+
+    #[test]
+    fn test_analyze_history_mailmap() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        std::fs::write(
+            temp_dir.path().join(".mailmap"),
+            "Alice Smith <alice@example.com> <alice@old-laptop.local>\n\
+             Alice Smith <alice@example.com> asmith <alice@example.com>\n",
+        )
+        .unwrap();
+
+        let time = Time::new(1_700_000_000, 0);
+        let laptop = Signature::new("alice", "alice@old-laptop.local", &time).unwrap();
+        let short = Signature::new("asmith", "alice@example.com", &time).unwrap();
+        let full = Signature::new("Alice Smith", "Alice@Example.com", &time).unwrap();
+        commit_files_as(&repo, &[("a.rs", "fn a() {}\n")], &laptop);
+        commit_files_as(&repo, &[("b.rs", "fn b() {}\n")], &short);
+        commit_files_as(&repo, &[("c.rs", "fn c() {}\n")], &full);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.by_author.len(), 1);
+        assert_eq!(stats.by_author["Alice Smith"].commits, 3);
+
+        // Grouping by email merges identities that differ only in case
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .group_by_email(true)
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.by_author.len(), 1);
+        assert_eq!(stats.by_author["alice@example.com"].commits, 3);
+    }
+
+    #[test]
+    fn test_co_author_credit_share() {
+        let stats = FileStats {
+            blank: 3,
+            comment: 4,
+            doc: 5,
+            code: 10,
+            mixed: 0,
+        };
+
+        let (author, co_author) = CoAuthorCredit::Full.share(stats, 2);
+        assert_eq!(author, stats);
+        assert_eq!(co_author, stats);
+
+        let (author, co_author) = CoAuthorCredit::Split.share(stats, 2);
+        assert_eq!(co_author.code, 3);
+        assert_eq!(author.code, 4);
+        assert_eq!(author.blank + co_author.blank * 2, 3);
+        assert_eq!(author.comment + co_author.comment * 2, 4);
+        assert_eq!(author.doc + co_author.doc * 2, 5);
+
         assert_eq!(
-            GitAnalyzer::classify_diff_line(b"# comment"),
-            LineType::Comment
+            CoAuthorCredit::from_str("Split", true),
+            Ok(CoAuthorCredit::Split)
         );
+        assert!(CoAuthorCredit::from_str("half", true).is_err());
+    }
+
+    #[test]
+    fn test_parse_identity() {
         assert_eq!(
-            GitAnalyzer::classify_diff_line(b"/* comment"),
-            LineType::Comment
+            GitAnalyzer::parse_identity("Jane Doe <jane@example.com>"),
+            Some(("Jane Doe", "jane@example.com"))
+        );
+        assert_eq!(GitAnalyzer::parse_identity("Jane Doe"), None);
+        assert_eq!(GitAnalyzer::parse_identity("<jane@example.com>"), None);
+    }
+
+    #[test]
+    fn test_analyze_history_co_authors() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let alice =
+            Signature::new("Alice", "alice@example.com", &Time::new(1_700_000_000, 0)).unwrap();
+        commit_files_with_message(
+            &repo,
+            &[("pair.rs", "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n")],
+            &alice,
+            "Pair on feature\n\nCo-authored-by: Bob <bob@example.com>\n\
+             co-authored-by: Alice <alice@example.com>\n",
+        );
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.by_author.len(), 2);
+        assert_eq!(stats.by_author["Alice"].commits, 1);
+        assert_eq!(stats.by_author["Alice"].additions.code, 4);
+        assert_eq!(stats.by_author["Bob"].commits, 0);
+        assert_eq!(stats.by_author["Bob"].co_authored_commits, 1);
+        assert_eq!(stats.by_author["Bob"].additions.code, 4);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .co_author_credit(CoAuthorCredit::Split)
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.by_author["Alice"].additions.code, 2);
+        assert_eq!(stats.by_author["Bob"].additions.code, 2);
+
+        // A matching co-author is enough to include the commit
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .authors(AuthorFilter::new(&["Bob".to_string()]).unwrap())
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.total_commits, 1);
+        assert_eq!(stats.by_author.len(), 1);
+        assert!(stats.by_author.contains_key("Bob"));
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .co_author_credit(CoAuthorCredit::None)
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.by_author.len(), 1);
+    }
+
+    /// Commit a flat set of files with explicit parents, without moving HEAD.
+    fn commit_tree(repo: &Repository, files: &[(&str, &str)], parents: &[Oid], time: i64) -> Oid {
+        commit_tree_dated(repo, files, parents, time, time)
+    }
+
+    /// Like [`commit_tree`], with separate author and committer times.
+    fn commit_tree_dated(
+        repo: &Repository,
+        files: &[(&str, &str)],
+        parents: &[Oid],
+        author_time: i64,
+        commit_time: i64,
+    ) -> Oid {
+        let mut builder = repo.treebuilder(None).unwrap();
+        for (name, content) in files {
+            let blob = repo.blob(content.as_bytes()).unwrap();
+            builder.insert(name, blob, 0o100644).unwrap();
+        }
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let parents: Vec<_> = parents
+            .iter()
+            .map(|p| repo.find_commit(*p).unwrap())
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        let author =
+            Signature::new("Test", "test@example.com", &Time::new(author_time, 0)).unwrap();
+        let committer =
+            Signature::new("Test", "test@example.com", &Time::new(commit_time, 0)).unwrap();
+        repo.commit(None, &author, &committer, "commit", &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_since_cutoff() {
+        let day = |d: i64| Time::new(1_704_067_200 + d * 86_400, 0); // 2024-01-01 + d
+        let since = NaiveDate::from_ymd_opt(2024, 1, 10)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+
+        let mut cutoff = SinceCutoff::new(Some(since));
+        assert!(!cutoff.is_done(day(12)));
+        // The day before `since` could still be in range in another time zone
+        assert!(!cutoff.is_done(day(8)));
+        for _ in 0..SINCE_SLOP - 1 {
+            assert!(!cutoff.is_done(day(1)));
+        }
+        // A newer commit after some skewed ones starts the run over
+        assert!(!cutoff.is_done(day(11)));
+        for _ in 0..SINCE_SLOP - 1 {
+            assert!(!cutoff.is_done(day(1)));
+        }
+        assert!(cutoff.is_done(day(1)));
+
+        assert!(!SinceCutoff::new(None).is_done(day(-1000)));
+    }
+
+    #[test]
+    fn test_analyze_history_skewed_dates() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let day = |d: i64| 1_704_067_200 + d * 86_400; // 2024-01-01 + d
+
+        // A side branch with one commit made on a machine whose clock was years
+        // behind, so it's dated before its own parent
+        let base = commit_tree(&repo, &[("a.rs", "fn a() {}\n")], &[], day(0));
+        let before_skew = commit_tree(
+            &repo,
+            &[("a.rs", "fn a() {}\n"), ("c.rs", "fn c() {}\nfn d() {}\n")],
+            &[base],
+            day(18),
+        );
+        let skewed = commit_tree(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\n"),
+                ("b.rs", "fn b() {}\n"),
+                ("c.rs", "fn c() {}\nfn d() {}\n"),
+            ],
+            &[before_skew],
+            day(-1000),
+        );
+        let side_files = [
+            ("a.rs", "fn a() {}\n"),
+            ("b.rs", "fn b() {}\n"),
+            ("c.rs", "fn c() {}\nfn d() {}\n"),
+            ("e.rs", "fn e() {}\nfn f() {}\nfn g() {}\n"),
+        ];
+        let side = commit_tree(&repo, &side_files, &[skewed], day(20));
+        let main = commit_tree(
+            &repo,
+            &[("a.rs", "fn a() {}\n"), ("m.rs", "fn m() {}\n")],
+            &[base],
+            day(25),
+        );
+        let mut merged_files = side_files.to_vec();
+        merged_files.push(("m.rs", "fn m() {}\n"));
+        let merge = commit_tree(&repo, &merged_files, &[main, side], day(30));
+        repo.set_head_detached(merge).unwrap();
+
+        // Neither the old root nor the skewed commit may hide the commit behind
+        // them, which is still in range
+        let since = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(Some(since), None, false)
+            .unwrap();
+
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(total_code_added(&stats), 6);
+        assert_eq!(stats.merges_skipped, 1);
+
+        let timeline = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .timeline(Period::Day, Some(since), None, false)
+            .unwrap();
+        assert_eq!(timeline.len(), 2);
+    }
+
+    #[test]
+    fn test_analyze_history_author_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        let first = commit_tree(&repo, &[("a.rs", "fn a() {}\n")], &[], 1_673_308_800); // 2023-01-10
+
+        // Cherry-picked in 2024-02-01 from a change authored in 2020-05-01
+        let picked = commit_tree_dated(
+            &repo,
+            &[("a.rs", "fn a() {}\n"), ("b.rs", "fn b() {}\nfn c() {}\n")],
+            &[first],
+            1_588_291_200,
+            1_706_745_600,
+        );
+        let last = commit_tree(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\n"),
+                ("b.rs", "fn b() {}\nfn c() {}\n"),
+                ("d.rs", "fn d() {}\nfn e() {}\nfn f() {}\n"),
+            ],
+            &[picked],
+            1_709_251_200, // 2024-03-01
+        );
+        repo.set_head_detached(last).unwrap();
+
+        let analyze = |source: DateSource, since: Option<&str>| {
+            let since = since.map(|d| {
+                NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap()
+            });
+            GitAnalyzer::new(temp_dir.path())
+                .unwrap()
+                .date_source(source)
+                .analyze_history(since, None, false)
+                .unwrap()
+        };
+
+        let by_author = analyze(DateSource::Author, None);
+        let dates: Vec<_> = by_author.daily.iter().map(|d| d.date.to_string()).collect();
+        assert_eq!(dates, vec!["2024-03-01", "2023-01-10", "2020-05-01"]);
+
+        // The old cherry-picked change is skipped without ending the walk
+        let since_2023 = analyze(DateSource::Author, Some("2023-01-01"));
+        assert_eq!(since_2023.total_commits, 2);
+        assert_eq!(total_code_added(&since_2023), 4);
+
+        let by_committer = analyze(DateSource::Committer, Some("2023-01-01"));
+        assert_eq!(by_committer.total_commits, 3);
+        assert_eq!(by_committer.daily[1].date.to_string(), "2024-02-01");
+
+        assert_eq!(DateSource::from_str("Author", true), Ok(DateSource::Author));
+        assert!(DateSource::from_str("authored", true).is_err());
+    }
+
+    #[test]
+    fn test_analyze_history_author_date_after_commit_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let day = |d: i64| 1_704_067_200 + d * 86_400; // 2024-01-01 + d
+
+        // Imported history whose root commit is authored after it was committed,
+        // followed by enough commits before `since` to end a committer date walk
+        let mut files = vec![("a.rs", "fn a() {}\n")];
+        let mut head = commit_tree_dated(&repo, &files, &[], day(20), day(0));
+        for (i, name) in ["b.rs", "c.rs", "d.rs", "e.rs", "f.rs"]
+            .into_iter()
+            .enumerate()
+        {
+            files.push((name, "fn f() {}\n"));
+            head = commit_tree(&repo, &files, &[head], day(i as i64 + 1));
+        }
+        repo.set_head_detached(head).unwrap();
+
+        let since = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .date_source(DateSource::Author)
+            .analyze_history(Some(since), None, false)
+            .unwrap();
+
+        assert_eq!(stats.total_commits, 1);
+        assert_eq!(total_code_added(&stats), 1);
+        assert_eq!(stats.daily[0].date.to_string(), "2024-01-21");
+
+        // By committer date, everything is out of range and the walk stops early
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(Some(since), None, false)
+            .unwrap();
+        assert_eq!(stats.total_commits, 0);
+    }
+
+    /// Build a repository with a merge whose resolution adds one line of its own.
+    fn merge_repo() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        let base_files = [("a.rs", "fn a() {}\n")];
+        let base = commit_tree(&repo, &base_files, &[], 1_700_000_000);
+        let main = commit_tree(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\n"),
+                ("main.rs", "fn m() {}\nfn n() {}\n"),
+            ],
+            &[base],
+            1_700_000_100,
+        );
+        let feature = commit_tree(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\n"),
+                ("feat.rs", "fn f() {}\nfn g() {}\nfn h() {}\n"),
+            ],
+            &[base],
+            1_700_000_200,
+        );
+        let merge = commit_tree(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\nfn evil() {}\n"),
+                ("main.rs", "fn m() {}\nfn n() {}\n"),
+                ("feat.rs", "fn f() {}\nfn g() {}\nfn h() {}\n"),
+            ],
+            &[main, feature],
+            1_700_000_300,
         );
+        repo.set_head_detached(merge).unwrap();
+
+        temp_dir
+    }
+
+    fn total_code_added(stats: &HistoricalStats) -> usize {
+        stats.daily.iter().map(|d| d.additions.code).sum()
+    }
+
+    #[test]
+    fn test_merge_policy_skip() {
+        let temp_dir = merge_repo();
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .merge_policy(MergePolicy::Skip)
+            .analyze_history(None, None, false)
+            .unwrap();
+
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.merges_skipped, 1);
+        assert_eq!(total_code_added(&stats), 6);
+    }
+
+    #[test]
+    fn test_merge_policy_default() {
+        let temp_dir = merge_repo();
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(None, None, false)
+            .unwrap();
+
+        // Merges are skipped unless asked otherwise, instead of diffing them
+        // against their first parent, which counted feat.rs a second time
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.merges_skipped, 1);
+        assert_eq!(total_code_added(&stats), 6);
+    }
+
+    #[test]
+    fn test_merge_policy_skip_counts_merges_in_paths() {
+        let temp_dir = merge_repo();
+        let merges_skipped = |file: &str| {
+            GitAnalyzer::new(temp_dir.path())
+                .unwrap()
+                .include(vec![file.to_string()])
+                .analyze_history(None, None, false)
+                .unwrap()
+                .merges_skipped
+        };
+
+        // The merge only took feat.rs from one side, but edited a.rs itself
+        assert_eq!(merges_skipped("feat.rs"), 0);
+        assert_eq!(merges_skipped("a.rs"), 1);
+    }
+
+    #[test]
+    fn test_merge_policy_first_parent() {
+        let temp_dir = merge_repo();
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .merge_policy(MergePolicy::FirstParent)
+            .analyze_history(None, None, false)
+            .unwrap();
+
+        // base, main and the merge, which brings in the feature branch
+        assert_eq!(stats.total_commits, 3);
+        assert_eq!(stats.merges_skipped, 0);
+        assert_eq!(total_code_added(&stats), 7);
+    }
+
+    #[test]
+    fn test_merge_policy_all_parents() {
+        let temp_dir = merge_repo();
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .merge_policy(MergePolicy::AllParents)
+            .analyze_history(None, None, false)
+            .unwrap();
+
+        // Every commit, with the merge only contributing its own line
+        assert_eq!(stats.total_commits, 4);
+        assert_eq!(stats.merges_skipped, 0);
+        assert_eq!(total_code_added(&stats), 7);
+    }
+
+    #[test]
+    fn test_merge_policy_from_str() {
+        assert_eq!(MergePolicy::from_str("skip", true), Ok(MergePolicy::Skip));
         assert_eq!(
-            GitAnalyzer::classify_diff_line(b"-- SQL comment"),
-            LineType::Comment
+            MergePolicy::from_str("first-parent", true),
+            Ok(MergePolicy::FirstParent)
+        );
+        assert_eq!(
+            MergePolicy::from_str("All-Parents", true),
+            Ok(MergePolicy::AllParents)
+        );
+        assert!(MergePolicy::from_str("octopus", true).is_err());
+    }
+
+    #[test]
+    fn test_analyze_history_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let content: String = (0..20).map(|i| format!("fn f{}() {{}}\n", i)).collect();
+        let base = commit_tree(&repo, &[("old.rs", &content)], &[], 1_700_000_000);
+        let renamed = format!("{}fn extra() {{}}\n", content);
+        let head = commit_tree(&repo, &[("new.rs", &renamed)], &[base], 1_700_000_100);
+        repo.set_head_detached(head).unwrap();
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.renames, 1);
+        // Only the line added while moving the file counts on top of the original
+        assert_eq!(total_code_added(&stats), 21);
+        assert_eq!(stats.daily[0].deletions.code, 0);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .rename_threshold(None)
+            .analyze_history(None, None, false)
+            .unwrap();
+        assert_eq!(stats.renames, 0);
+        assert_eq!(total_code_added(&stats), 41);
+    }
+
+    #[test]
+    fn test_analyze_history_renames_across_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let content: String = (0..20).map(|i| format!("fn f{}() {{}}\n", i)).collect();
+        commit_files(&repo, &[("lib/util.rs", &content)], 1_700_000_000);
+
+        let remove = |path: &str| {
+            let mut index = repo.index().unwrap();
+            index.remove_path(Path::new(path)).unwrap();
+            std::fs::remove_file(temp_dir.path().join(path)).unwrap();
+            index.write().unwrap();
+        };
+
+        // Moved into src/ with one new line, then moved back out unchanged
+        let moved = format!("{}fn extra() {{}}\n", content);
+        remove("lib/util.rs");
+        commit_files(&repo, &[("src/util.rs", &moved)], 1_700_000_100);
+        remove("src/util.rs");
+        commit_files(&repo, &[("lib/util.rs", &moved)], 1_700_000_200);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .paths(vec![temp_dir.path().join("src")])
+            .analyze_history(None, None, false)
+            .unwrap();
+
+        // Both moves are renames, not a whole file added to and deleted from src/
+        assert_eq!(stats.total_commits, 2);
+        assert_eq!(stats.renames, 2);
+        assert_eq!(total_code_added(&stats), 1);
+        assert_eq!(stats.daily[0].deletions.code, 0);
+    }
+
+    #[test]
+    fn test_analyze_history_hotspots() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let content: String = (0..20).map(|i| format!("fn f{}() {{}}\n", i)).collect();
+        let alice =
+            Signature::new("Alice", "alice@example.com", &Time::new(1_700_000_000, 0)).unwrap();
+        let bob = Signature::new("Bob", "bob@example.com", &Time::new(1_700_000_100, 0)).unwrap();
+        commit_files_as(
+            &repo,
+            &[("old.rs", &content), ("lib.rs", "fn a() {}\n")],
+            &alice,
         );
+        commit_files_as(
+            &repo,
+            &[("old.rs", &format!("{}fn b() {{}}\n", content))],
+            &bob,
+        );
+
+        // Move old.rs to new.rs, adding a line on the way
+        let moved = format!("{}fn b() {{}}\nfn c() {{}}\n", content);
+        let mut index = repo.index().unwrap();
+        index.remove_path(Path::new("old.rs")).unwrap();
+        std::fs::remove_file(temp_dir.path().join("old.rs")).unwrap();
+        index.write().unwrap();
+        commit_files(&repo, &[("new.rs", &moved)], 1_700_000_200);
+
+        let analyzer = GitAnalyzer::new(temp_dir.path()).unwrap();
+        let mut stats = analyzer.analyze_history(None, None, false).unwrap();
+
+        // Changes made before the rename are credited to the new path
+        assert!(!stats.by_file.contains_key("old.rs"));
+        let new = &stats.by_file["new.rs"];
+        assert_eq!(new.commits, 3);
+        assert_eq!(new.authors, 3);
+        assert_eq!(new.additions.code, 22);
+        assert_eq!(new.churn(), 22);
+        assert_eq!(stats.by_file["lib.rs"].commits, 1);
+
+        let hotspots = stats.hotspots(false);
+        assert_eq!(hotspots[0].0, "new.rs");
+        assert_eq!(hotspots[1].0, "lib.rs");
+
+        analyzer.measure_hotspots(&mut stats);
+        assert_eq!(stats.by_file["new.rs"].current_code, Some(22));
+        assert_eq!(stats.by_file["new.rs"].weighted_score(), 66);
+
+        std::fs::remove_file(temp_dir.path().join("lib.rs")).unwrap();
+        analyzer.measure_hotspots(&mut stats);
+        assert_eq!(stats.by_file["lib.rs"].current_code, None);
+        let weighted = stats.hotspots(true);
+        assert_eq!(weighted.len(), 1);
+        assert_eq!(weighted[0].0, "new.rs");
     }
 
     #[test]
-    fn test_classify_diff_line_code() {
+    fn test_ownership() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let alice =
+            Signature::new("Alice", "alice@example.com", &Time::new(1_700_000_000, 0)).unwrap();
+        let bob = Signature::new("Bob", "bob@example.com", &Time::new(1_700_000_100, 0)).unwrap();
+        commit_files_as(
+            &repo,
+            &[
+                (
+                    "src/lib.rs",
+                    "// header\nfn a() {}\nfn b() {}\n\nfn c() {}\nfn d() {}\nfn e() {}\n",
+                ),
+                ("README.md", "# Title\n"),
+            ],
+            &alice,
+        );
+        commit_files_as(
+            &repo,
+            &[(
+                "src/lib.rs",
+                "// header\nfn a() {}\nfn b2() {}\n\nfn c() {}\nfn d() {}\nfn e() {}\n",
+            )],
+            &bob,
+        );
+
+        // Uncommitted edit in the middle of a hunk, and a file that was never committed
+        let lib = temp_dir.path().join("src/lib.rs");
+        std::fs::write(
+            &lib,
+            "// header\nfn a() {}\nfn b2() {}\n\nfn c() {}\nfn d2() {}\nfn e() {}\n",
+        )
+        .unwrap();
+        let new = temp_dir.path().join("src/new.rs");
+        std::fs::write(&new, "fn n() {}\n").unwrap();
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .ownership(&[lib, new, temp_dir.path().join("README.md")], false)
+            .unwrap();
+
+        let lib = &stats.files["src/lib.rs"];
+        assert_eq!(lib.code, 5); // The comment and blank lines don't count
+        assert_eq!(lib.by_author["Alice"], 3);
+        assert_eq!(lib.by_author["Bob"], 1);
+        assert_eq!(lib.uncommitted, 1);
+        assert_eq!(stats.files["src/new.rs"].uncommitted, 1);
+        assert_eq!(stats.files["README.md"].by_author["Alice"], 1);
+
+        let total = stats.total();
+        assert_eq!(total.code, 7);
+        assert_eq!(total.by_author["Alice"], 4);
+        assert_eq!(stats.directories()["src"].code, 6);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let first = commit_files(
+            &repo,
+            &[
+                ("src/main.rs", "// entry\nfn main() {}\n"),
+                ("src/node_modules/dep.js", "let z = 1;\n"),
+                (".github/check.py", "print(1)\n"),
+                ("tools/gen.py", "x = 1\n\ny = 2\n"),
+            ],
+            1_700_000_000,
+        );
+        commit_files(
+            &repo,
+            &[(
+                "src/main.rs",
+                "fn main() {}\nfn other() {}\nfn third() {}\n",
+            )],
+            1_700_100_000,
+        );
+
+        let analyzer = GitAnalyzer::new(temp_dir.path()).unwrap();
+        let stats = analyzer.snapshot(&first.to_string()).unwrap();
+        let (files, totals) = stats.total();
+        assert_eq!(files, 2);
+        assert_eq!(totals.code, 3);
+        assert_eq!(totals.comment, 1);
+        assert_eq!(totals.blank, 1);
+
+        // The latest revision sees the rewritten file
+        let stats = analyzer.snapshot("HEAD").unwrap();
+        let rust = stats.get_languages()[1];
+        assert_eq!(rust.language, "Rust");
+        assert_eq!(rust.stats.code, 3);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .paths(vec![temp_dir.path().join("src")])
+            .hidden(true)
+            .snapshot(&first.to_string())
+            .unwrap();
+        assert_eq!(stats.total().0, 1);
+
+        let stats = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .hidden(true)
+            .snapshot(&first.to_string())
+            .unwrap();
+        let python = stats.get_languages()[0];
+        assert_eq!(python.language, "Python");
+        assert_eq!(python.files, 2);
+
+        assert!(analyzer.snapshot("no-such-tag").is_err());
+    }
+
+    #[test]
+    fn test_analyze_history_timezone() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        // 2024-01-15 23:30 UTC, made at 01:30 the next morning in UTC+2
+        let sig =
+            Signature::new("Test", "test@example.com", &Time::new(1_705_361_400, 120)).unwrap();
+        commit_files_as(&repo, &[("a.rs", "fn a() {}\n")], &sig);
+
+        let dates = |timezone: &str| {
+            let stats = GitAnalyzer::new(temp_dir.path())
+                .unwrap()
+                .timezone(timezone.parse().unwrap())
+                .analyze_history(None, None, false)
+                .unwrap();
+            stats.daily[0].date.to_string()
+        };
+        assert_eq!(dates("commit"), "2024-01-16");
+        assert_eq!(dates("utc"), "2024-01-15");
+        assert_eq!(dates("-08:00"), "2024-01-15");
+
+        // Bounds are wall-clock times in the same zone
+        let jan_16 = NaiveDate::from_ymd_opt(2024, 1, 16)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let count = |timezone: &str| {
+            GitAnalyzer::new(temp_dir.path())
+                .unwrap()
+                .timezone(timezone.parse().unwrap())
+                .analyze_history(Some(jan_16), None, false)
+                .unwrap()
+                .total_commits
+        };
+        assert_eq!(count("commit"), 1);
+        assert_eq!(count("utc"), 0);
+    }
+
+    #[test]
+    fn test_period_bucket_start() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap(); // A Thursday
+        assert_eq!(Period::Day.bucket_start(date), date);
+        assert_eq!(
+            Period::Week.bucket_start(date),
+            NaiveDate::from_ymd_opt(2024, 3, 11).unwrap()
+        );
+        assert_eq!(
+            Period::Month.bucket_start(date),
+            NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+        );
+        assert_eq!(
+            Period::Quarter.bucket_start(date),
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
         assert_eq!(
-            GitAnalyzer::classify_diff_line(b"let x = 5;"),
-            LineType::Code
+            Period::Quarter.bucket_start(NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()),
+            NaiveDate::from_ymd_opt(2024, 10, 1).unwrap()
         );
         assert_eq!(
-            GitAnalyzer::classify_diff_line(b"fn main() {"),
-            LineType::Code
+            Period::Year.bucket_start(date),
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
         );
     }
 
+    #[test]
+    fn test_aggregate_periods() {
+        let day = |y: i32, m: u32, d: u32, code: usize| DailyStats {
+            date: NaiveDate::from_ymd_opt(y, m, d).unwrap(),
+            additions: FileStats {
+                blank: 0,
+                comment: 0,
+                doc: 0,
+                code,
+                mixed: 0,
+            },
+            net_code: code as i64,
+            ..DailyStats::default()
+        };
+        let stats = HistoricalStats {
+            daily: vec![
+                day(2025, 2, 3, 1),
+                day(2024, 12, 30, 2), // ISO week 1 of 2025
+                day(2024, 11, 5, 4),
+                day(2024, 3, 14, 8),
+            ],
+            ..HistoricalStats::default()
+        };
+
+        let sizes = |period| -> Vec<usize> {
+            stats
+                .aggregate(period)
+                .iter()
+                .map(|d| d.additions.code)
+                .collect()
+        };
+        assert_eq!(sizes(Period::Day), vec![1, 2, 4, 8]);
+        assert_eq!(sizes(Period::Week), vec![1, 2, 4, 8]);
+        assert_eq!(sizes(Period::Month), vec![1, 2, 4, 8]);
+        assert_eq!(sizes(Period::Quarter), vec![1, 6, 8]);
+        assert_eq!(sizes(Period::Year), vec![1, 14]);
+
+        let yearly = stats.aggregate(Period::Year);
+        assert_eq!(yearly[1].date, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(yearly[1].net_code, 14);
+        assert_eq!(
+            stats.aggregate_by_week()[1].date,
+            NaiveDate::from_ymd_opt(2024, 12, 30).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timeline() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let day = 86_400;
+        let start = 1_704_067_200; // 2024-01-01, a Monday
+
+        commit_files(&repo, &[("a.rs", "fn a() {}\n")], start);
+        commit_files(&repo, &[("b.rs", "fn b() {}\n")], start + day);
+        let last_of_week = commit_files(&repo, &[("a.md", "# Notes\n")], start + 2 * day);
+        commit_files(&repo, &[("a.rs", "fn a() {}\n// done\n")], start + 8 * day);
+
+        let analyzer = GitAnalyzer::new(temp_dir.path()).unwrap();
+        let weekly = analyzer.timeline(Period::Week, None, None, false).unwrap();
+        assert_eq!(weekly.len(), 2);
+
+        // Most recent week first, sampled at its last commit
+        let (files, totals) = weekly[0].stats.total();
+        assert_eq!(weekly[0].date, NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        assert_eq!((files, totals.code, totals.comment), (3, 3, 1));
+        assert_eq!(weekly[1].date, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(weekly[1].commit, last_of_week.to_string());
+        assert_eq!(weekly[1].stats.total().1.code, 3);
+
+        let daily = analyzer.timeline(Period::Day, None, None, false).unwrap();
+        assert_eq!(daily.len(), 4);
+        assert_eq!(daily[3].stats.total().1.code, 1);
+
+        let since = NaiveDate::from_ymd_opt(2024, 1, 2)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let until = NaiveDate::from_ymd_opt(2024, 1, 3)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let range = analyzer
+            .timeline(Period::Day, Some(since), Some(until), false)
+            .unwrap();
+        assert_eq!(range.len(), 2);
+    }
+
+    #[test]
+    fn test_timeline_author_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let day = |d: i64| 1_704_067_200 + d * 86_400; // 2024-01-01 + d
+
+        let first = commit_tree(&repo, &[("a.rs", "fn a() {}\n")], &[], day(4));
+        let last_of_january = commit_tree(
+            &repo,
+            &[("a.rs", "fn a() {}\n"), ("b.rs", "fn b() {}\n")],
+            &[first],
+            day(5),
+        );
+        // Cherry-picked in March from a change authored on January 4th
+        let picked = commit_tree_dated(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\n"),
+                ("b.rs", "fn b() {}\n"),
+                ("c.rs", "fn c() {}\n"),
+            ],
+            &[last_of_january],
+            day(3),
+            day(60),
+        );
+        repo.set_head_detached(picked).unwrap();
+
+        // The picked commit comes first in the walk, but doesn't close January
+        let monthly = GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .date_source(DateSource::Author)
+            .timeline(Period::Month, None, None, false)
+            .unwrap();
+        assert_eq!(monthly.len(), 1);
+        assert_eq!(monthly[0].commit, last_of_january.to_string());
+        assert_eq!(monthly[0].stats.total().1.code, 2);
+    }
+
+    /// Build a repository with `main` and a `feature` branch forked from it.
+    fn branch_repo() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+
+        let base = commit_tree(&repo, &[("a.rs", "fn a() {}\n")], &[], 1_700_000_000);
+        let main = commit_tree(
+            &repo,
+            &[("a.rs", "fn a() {}\n"), ("m.rs", "fn m() {}\nfn n() {}\n")],
+            &[base],
+            1_700_000_100,
+        );
+        let feature = commit_tree(
+            &repo,
+            &[
+                ("a.rs", "fn a() {}\n"),
+                ("f.rs", "fn f() {}\nfn g() {}\nfn h() {}\n"),
+            ],
+            &[base],
+            1_700_000_200,
+        );
+        repo.branch("main-line", &repo.find_commit(main).unwrap(), true)
+            .unwrap();
+        repo.branch("feature", &repo.find_commit(feature).unwrap(), true)
+            .unwrap();
+        repo.set_head("refs/heads/main-line").unwrap();
+
+        temp_dir
+    }
+
+    #[test]
+    fn test_analyze_history_revision() {
+        let temp_dir = branch_repo();
+        let analyze = |revision: Option<&str>, all: bool| {
+            GitAnalyzer::new(temp_dir.path())
+                .unwrap()
+                .revision(revision.map(|r| r.to_string()))
+                .all_branches(all)
+                .analyze_history(None, None, false)
+                .unwrap()
+        };
+
+        let head = analyze(None, false);
+        assert_eq!(head.total_commits, 2);
+        assert_eq!(total_code_added(&head), 3);
+
+        let feature = analyze(Some("feature"), false);
+        assert_eq!(feature.total_commits, 2);
+        assert_eq!(total_code_added(&feature), 4);
+
+        let range = analyze(Some("main-line..feature"), false);
+        assert_eq!(range.total_commits, 1);
+        assert_eq!(total_code_added(&range), 3);
+
+        let symmetric = analyze(Some("main-line...feature"), false);
+        assert_eq!(symmetric.total_commits, 2);
+        assert_eq!(total_code_added(&symmetric), 5);
+
+        // The shared base commit is only counted once
+        let all = analyze(None, true);
+        assert_eq!(all.total_commits, 3);
+        assert_eq!(total_code_added(&all), 6);
+
+        assert!(GitAnalyzer::new(temp_dir.path())
+            .unwrap()
+            .revision(Some("no-such-branch".to_string()))
+            .analyze_history(None, None, false)
+            .is_err());
+    }
+
     #[test]
     fn test_is_git_repo() {
         // Current directory should be a git repo (sniffy project)
@@ -19,6 +19,74 @@ impl CommentPair {
     }
 }
 
+/// Represents the delimiters of a string literal.
+///
+/// Comment markers inside a string literal are part of the string.
+#[derive(Debug, Clone)]
+pub struct StringDelimiter {
+    pub start: &'static str,
+    pub end: &'static str,
+    /// Character that escapes the next one, if the string has escapes.
+    pub escape: Option<char>,
+    /// Whether the string may span several lines.
+    pub multi_line: bool,
+    /// Whether the string holds exactly one character or escape sequence.
+    ///
+    /// A start delimiter that isn't closed right after one is not a string,
+    /// like the `'` of a Rust lifetime or loop label.
+    pub char_literal: bool,
+}
+
+impl StringDelimiter {
+    /// A single-line string with backslash escapes.
+    pub const fn new(start: &'static str, end: &'static str) -> Self {
+        Self {
+            start,
+            end,
+            escape: Some('\\'),
+            multi_line: false,
+            char_literal: false,
+        }
+    }
+
+    /// Disable escapes, as in raw strings.
+    pub const fn raw(self) -> Self {
+        Self {
+            escape: None,
+            ..self
+        }
+    }
+
+    /// Allow the string to span several lines.
+    pub const fn multi_line(self) -> Self {
+        Self {
+            multi_line: true,
+            ..self
+        }
+    }
+
+    /// Only match a single character or escape sequence, as in char literals.
+    pub const fn char_literal(self) -> Self {
+        Self {
+            char_literal: true,
+            ..self
+        }
+    }
+}
+
+/// Double and single quoted strings with backslash escapes, as in C.
+const QUOTED_STRINGS: &[StringDelimiter] = &[
+    StringDelimiter::new("\"", "\""),
+    StringDelimiter::new("'", "'"),
+];
+
+/// Javadoc-style `/** */` documentation comments.
+const JAVADOC_COMMENTS: &[CommentPair] = &[CommentPair::new("/**", "*/")];
+
+/// `/** */` and `/*! */` documentation comments, as in Rust and Doxygen.
+const BLOCK_DOC_COMMENTS: &[CommentPair] =
+    &[CommentPair::new("/**", "*/"), CommentPair::new("/*!", "*/")];
+
 /// Information about a programming language.
 #[derive(Debug, Clone)]
 pub struct LanguageInfo {
@@ -26,6 +94,16 @@ pub struct LanguageInfo {
     pub extensions: &'static [&'static str],
     pub single_line_comments: &'static [&'static str],
     pub multi_line_comments: &'static [CommentPair],
+    /// Single-line comment markers that start documentation, like Rust's `///`.
+    pub doc_comments: &'static [&'static str],
+    /// Multi-line comment delimiters that start documentation, like Javadoc's `/**`.
+    pub multi_line_doc_comments: &'static [CommentPair],
+    pub strings: &'static [StringDelimiter],
+    /// Whether multi-line comments nest, as in Rust's `/* /* */ */`.
+    pub nested_comments: bool,
+    /// Whether a multi-line string on its own as a statement is documentation,
+    /// as in Python's docstrings.
+    pub docstrings: bool,
 }
 
 impl LanguageInfo {
@@ -40,6 +118,45 @@ impl LanguageInfo {
             extensions,
             single_line_comments,
             multi_line_comments,
+            doc_comments: &[],
+            multi_line_doc_comments: &[],
+            strings: &[],
+            nested_comments: false,
+            docstrings: false,
+        }
+    }
+
+    /// Set the documentation comment markers of the language.
+    pub const fn with_doc_comments(
+        self,
+        doc_comments: &'static [&'static str],
+        multi_line_doc_comments: &'static [CommentPair],
+    ) -> Self {
+        Self {
+            doc_comments,
+            multi_line_doc_comments,
+            ..self
+        }
+    }
+
+    /// Set the string literal delimiters of the language.
+    pub const fn with_strings(self, strings: &'static [StringDelimiter]) -> Self {
+        Self { strings, ..self }
+    }
+
+    /// Make multi-line strings that are bare statements count as documentation.
+    pub const fn with_docstrings(self) -> Self {
+        Self {
+            docstrings: true,
+            ..self
+        }
+    }
+
+    /// Make multi-line comments nest, so each start needs its own end.
+    pub const fn with_nested_comments(self) -> Self {
+        Self {
+            nested_comments: true,
+            ..self
         }
     }
 }
@@ -52,55 +169,98 @@ pub const LANGUAGES: &[LanguageInfo] = &[
         &["js", "jsx", "mjs", "cjs"],
         &["//"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&[], JAVADOC_COMMENTS)
+    .with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+        StringDelimiter::new("`", "`").multi_line(),
+    ]),
     // TypeScript
     LanguageInfo::new(
         "TypeScript",
         &["ts", "tsx"],
         &["//"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&[], JAVADOC_COMMENTS)
+    .with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+        StringDelimiter::new("`", "`").multi_line(),
+    ]),
     // Python
-    LanguageInfo::new(
-        "Python",
-        &["py", "pyw"],
-        &["#"],
-        &[
-            CommentPair::new("\"\"\"", "\"\"\""),
-            CommentPair::new("'''", "'''"),
-        ],
-    ),
+    LanguageInfo::new("Python", &["py", "pyw"], &["#"], &[])
+        .with_strings(&[
+            StringDelimiter::new("\"\"\"", "\"\"\"").multi_line(),
+            StringDelimiter::new("'''", "'''").multi_line(),
+            StringDelimiter::new("\"", "\""),
+            StringDelimiter::new("'", "'"),
+        ])
+        .with_docstrings(),
     // Rust
-    LanguageInfo::new(
-        "Rust",
-        &["rs"],
-        &["//", "///", "//!"],
-        &[CommentPair::new("/*", "*/")],
-    ),
+    LanguageInfo::new("Rust", &["rs"], &["//"], &[CommentPair::new("/*", "*/")])
+        .with_doc_comments(&["///", "//!"], BLOCK_DOC_COMMENTS)
+        .with_strings(&[
+            StringDelimiter::new("\"", "\"").multi_line(),
+            StringDelimiter::new("'", "'").char_literal(),
+            StringDelimiter::new("r\"", "\"").raw().multi_line(),
+            StringDelimiter::new("r#\"", "\"#").raw().multi_line(),
+            StringDelimiter::new("r##\"", "\"##").raw().multi_line(),
+        ])
+        .with_nested_comments(),
     // Go
-    LanguageInfo::new("Go", &["go"], &["//"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("Go", &["go"], &["//"], &[CommentPair::new("/*", "*/")]).with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+        StringDelimiter::new("`", "`").raw().multi_line(),
+    ]),
     // Java
-    LanguageInfo::new("Java", &["java"], &["//"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("Java", &["java"], &["//"], &[CommentPair::new("/*", "*/")])
+        .with_doc_comments(&[], JAVADOC_COMMENTS)
+        .with_strings(&[
+            StringDelimiter::new("\"\"\"", "\"\"\"").multi_line(),
+            StringDelimiter::new("\"", "\""),
+            StringDelimiter::new("'", "'"),
+        ]),
     // C
-    LanguageInfo::new("C", &["c", "h"], &["//"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("C", &["c", "h"], &["//"], &[CommentPair::new("/*", "*/")])
+        .with_doc_comments(&["///", "//!"], BLOCK_DOC_COMMENTS)
+        .with_strings(QUOTED_STRINGS),
     // C++
     LanguageInfo::new(
         "C++",
         &["cpp", "cc", "cxx", "hpp", "hxx", "hh"],
         &["//"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&["///", "//!"], BLOCK_DOC_COMMENTS)
+    .with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+        StringDelimiter::new("R\"(", ")\"").raw().multi_line(),
+    ]),
     // C#
-    LanguageInfo::new("C#", &["cs"], &["//"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("C#", &["cs"], &["//"], &[CommentPair::new("/*", "*/")])
+        .with_doc_comments(&["///"], JAVADOC_COMMENTS)
+        .with_strings(&[
+            StringDelimiter::new("\"", "\""),
+            StringDelimiter::new("'", "'"),
+            StringDelimiter::new("@\"", "\"").raw().multi_line(),
+        ]),
     // Ruby
     LanguageInfo::new(
         "Ruby",
         &["rb"],
         &["#"],
         &[CommentPair::new("=begin", "=end")],
-    ),
+    )
+    .with_strings(QUOTED_STRINGS),
     // Shell
-    LanguageInfo::new("Shell", &["sh", "bash", "zsh"], &["#"], &[]),
+    LanguageInfo::new("Shell", &["sh", "bash", "zsh"], &["#"], &[]).with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'").raw(),
+    ]),
     // HTML
     LanguageInfo::new(
         "HTML",
@@ -109,11 +269,14 @@ pub const LANGUAGES: &[LanguageInfo] = &[
         &[CommentPair::new("<!--", "-->")],
     ),
     // CSS
-    LanguageInfo::new("CSS", &["css"], &[], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("CSS", &["css"], &[], &[CommentPair::new("/*", "*/")])
+        .with_strings(QUOTED_STRINGS),
     // SCSS
-    LanguageInfo::new("SCSS", &["scss"], &["//"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("SCSS", &["scss"], &["//"], &[CommentPair::new("/*", "*/")])
+        .with_strings(QUOTED_STRINGS),
     // Sass
-    LanguageInfo::new("Sass", &["sass"], &["//"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("Sass", &["sass"], &["//"], &[CommentPair::new("/*", "*/")])
+        .with_strings(QUOTED_STRINGS),
     // Markdown
     LanguageInfo::new(
         "Markdown",
@@ -124,7 +287,10 @@ pub const LANGUAGES: &[LanguageInfo] = &[
     // JSON
     LanguageInfo::new("JSON", &["json"], &[], &[]),
     // YAML
-    LanguageInfo::new("YAML", &["yaml", "yml"], &["#"], &[]),
+    LanguageInfo::new("YAML", &["yaml", "yml"], &["#"], &[]).with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'").raw(),
+    ]),
     // XML
     LanguageInfo::new("XML", &["xml"], &[], &[CommentPair::new("<!--", "-->")]),
     // PHP
@@ -133,58 +299,101 @@ pub const LANGUAGES: &[LanguageInfo] = &[
         &["php"],
         &["//", "#"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&[], JAVADOC_COMMENTS)
+    .with_strings(QUOTED_STRINGS),
     // Kotlin
     LanguageInfo::new(
         "Kotlin",
         &["kt", "kts"],
         &["//"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&[], JAVADOC_COMMENTS)
+    .with_strings(&[
+        StringDelimiter::new("\"\"\"", "\"\"\"").raw().multi_line(),
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+    ])
+    .with_nested_comments(),
     // Swift
     LanguageInfo::new(
         "Swift",
         &["swift"],
         &["//"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&["///"], JAVADOC_COMMENTS)
+    .with_strings(&[
+        StringDelimiter::new("\"\"\"", "\"\"\"").multi_line(),
+        StringDelimiter::new("\"", "\""),
+    ])
+    .with_nested_comments(),
     // Scala
     LanguageInfo::new(
         "Scala",
         &["scala"],
         &["//"],
         &[CommentPair::new("/*", "*/")],
-    ),
+    )
+    .with_doc_comments(&[], JAVADOC_COMMENTS)
+    .with_strings(&[
+        StringDelimiter::new("\"\"\"", "\"\"\"").raw().multi_line(),
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+    ])
+    .with_nested_comments(),
     // Elixir
-    LanguageInfo::new("Elixir", &["ex", "exs"], &["#"], &[]),
+    LanguageInfo::new("Elixir", &["ex", "exs"], &["#"], &[]).with_strings(&[
+        StringDelimiter::new("\"\"\"", "\"\"\"").multi_line(),
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+    ]),
     // Erlang
-    LanguageInfo::new("Erlang", &["erl", "hrl"], &["%"], &[]),
+    LanguageInfo::new("Erlang", &["erl", "hrl"], &["%"], &[]).with_strings(QUOTED_STRINGS),
     // Haskell
     LanguageInfo::new(
         "Haskell",
         &["hs", "lhs"],
         &["--"],
         &[CommentPair::new("{-", "-}")],
-    ),
+    )
+    .with_doc_comments(&["-- |", "-- ^"], &[CommentPair::new("{- |", "-}")])
+    .with_strings(QUOTED_STRINGS)
+    .with_nested_comments(),
     // Lua
-    LanguageInfo::new("Lua", &["lua"], &["--"], &[CommentPair::new("--[[", "]]")]),
+    LanguageInfo::new("Lua", &["lua"], &["--"], &[CommentPair::new("--[[", "]]")]).with_strings(&[
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'"),
+        StringDelimiter::new("[[", "]]").raw().multi_line(),
+    ]),
     // Perl
     LanguageInfo::new(
         "Perl",
         &["pl", "pm"],
         &["#"],
         &[CommentPair::new("=pod", "=cut")],
-    ),
+    )
+    .with_strings(QUOTED_STRINGS),
     // R
-    LanguageInfo::new("R", &["r", "R"], &["#"], &[]),
+    LanguageInfo::new("R", &["r", "R"], &["#"], &[]).with_strings(QUOTED_STRINGS),
     // SQL
-    LanguageInfo::new("SQL", &["sql"], &["--"], &[CommentPair::new("/*", "*/")]),
+    LanguageInfo::new("SQL", &["sql"], &["--"], &[CommentPair::new("/*", "*/")]).with_strings(&[
+        StringDelimiter::new("'", "'").raw(),
+        StringDelimiter::new("\"", "\"").raw(),
+    ]),
     // TOML
-    LanguageInfo::new("TOML", &["toml"], &["#"], &[]),
+    LanguageInfo::new("TOML", &["toml"], &["#"], &[]).with_strings(&[
+        StringDelimiter::new("\"\"\"", "\"\"\"").multi_line(),
+        StringDelimiter::new("'''", "'''").raw().multi_line(),
+        StringDelimiter::new("\"", "\""),
+        StringDelimiter::new("'", "'").raw(),
+    ]),
     // INI
     LanguageInfo::new("INI", &["ini", "cfg"], &[";", "#"], &[]),
     // Vim Script
-    LanguageInfo::new("Vim Script", &["vim"], &["\""], &[]),
+    LanguageInfo::new("Vim Script", &["vim"], &["\""], &[])
+        .with_strings(&[StringDelimiter::new("'", "'").raw()]),
 ];
 
 /// Language detector that maps file extensions to languages.
@@ -308,6 +517,58 @@ mod tests {
         assert_eq!(cpp_lang.unwrap().name, "C++");
     }
 
+    #[test]
+    fn test_string_delimiters() {
+        for lang in LANGUAGES {
+            for string in lang.strings {
+                assert!(
+                    !string.start.is_empty() && !string.end.is_empty(),
+                    "Language {} has an empty string delimiter",
+                    lang.name
+                );
+            }
+        }
+
+        let rust = LANGUAGES.iter().find(|l| l.name == "Rust").unwrap();
+        let raw = rust.strings.iter().find(|s| s.start == "r#\"").unwrap();
+        assert_eq!(raw.end, "\"#");
+        assert!(raw.escape.is_none());
+        assert!(raw.multi_line);
+    }
+
+    #[test]
+    fn test_doc_comments() {
+        for lang in LANGUAGES {
+            for marker in lang.doc_comments {
+                assert!(
+                    !lang.single_line_comments.contains(marker),
+                    "Language {} lists doc comment {} as a plain comment",
+                    lang.name,
+                    marker
+                );
+            }
+        }
+
+        let rust = LANGUAGES.iter().find(|l| l.name == "Rust").unwrap();
+        assert_eq!(rust.single_line_comments, ["//"]);
+        assert_eq!(rust.doc_comments, ["///", "//!"]);
+
+        let python = LANGUAGES.iter().find(|l| l.name == "Python").unwrap();
+        assert!(python.multi_line_comments.is_empty());
+        assert!(python.multi_line_doc_comments.is_empty());
+        assert!(python.docstrings);
+    }
+
+    #[test]
+    fn test_nested_comments() {
+        let nested: Vec<_> = LANGUAGES
+            .iter()
+            .filter(|l| l.nested_comments)
+            .map(|l| l.name)
+            .collect();
+        assert_eq!(nested, ["Rust", "Kotlin", "Swift", "Scala", "Haskell"]);
+    }
+
     #[test]
     fn test_all_languages_have_extensions() {
         for lang in LANGUAGES {
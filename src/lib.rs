@@ -48,6 +48,7 @@
 //! - [`git`]: Git repository analysis and history tracking
 //! - [`language`]: Language definitions and file extension detection
 //! - [`output`]: Output formatting (tables, JSON, CSV)
+//! - [`ownership`]: Code ownership attributed from git blame
 //! - [`processor`]: File processing and binary file detection
 //! - [`stats`]: Statistics data structures and aggregation
 //! - [`walker`]: Directory traversal with .gitignore support
@@ -88,6 +89,7 @@ pub mod error;
 pub mod git;
 pub mod language;
 pub mod output;
+pub mod ownership;
 pub mod processor;
 pub mod stats;
 pub mod walker;
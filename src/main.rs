@@ -1,6 +1,7 @@
+use chrono::NaiveDateTime;
 use rayon::prelude::*;
 use sniffy::cli::Cli;
-use sniffy::git::GitAnalyzer;
+use sniffy::git::{DailyStats, GitAnalyzer, HistoricalStats};
 use sniffy::output::OutputFormatter;
 use sniffy::processor::FileProcessor;
 use sniffy::stats::ProjectStats;
@@ -24,6 +25,24 @@ fn main() {
         return;
     }
 
+    // Handle timeline mode
+    if cli.timeline {
+        run_timeline_mode(&cli);
+        return;
+    }
+
+    // Handle ownership mode
+    if cli.ownership {
+        run_ownership_mode(&cli);
+        return;
+    }
+
+    // Handle revision snapshot mode
+    if let Some(revision) = &cli.at {
+        run_revision_mode(&cli, revision);
+        return;
+    }
+
     // Configure Rayon thread pool
     if cli.jobs > 0 {
         rayon::ThreadPoolBuilder::new()
@@ -59,7 +78,7 @@ fn main() {
     let project_stats = all_files
         .par_iter()
         .map(|file_path| {
-            let processor = FileProcessor::new();
+            let processor = FileProcessor::new().mixed_lines(cli.mixed_lines().unwrap_or_default());
             let mut local_stats = ProjectStats::new();
 
             if let Some((language, stats)) = processor.process_file(file_path) {
@@ -88,10 +107,16 @@ fn main() {
         );
     }
 
+    print_project_stats(&cli, &project_stats);
+}
+
+/// Format and print snapshot statistics based on the format option.
+fn print_project_stats(cli: &Cli, project_stats: &ProjectStats) {
     // Format and print results based on format option
+    let mixed_lines = cli.mixed_lines().unwrap_or_default();
     let format_lower = cli.format.to_lowercase();
     match format_lower.as_str() {
-        "json" => match OutputFormatter::format_json(&project_stats) {
+        "json" => match OutputFormatter::format_json(project_stats) {
             Ok(json) => println!("{}", json),
             Err(e) => {
                 eprintln!("Error formatting JSON: {}", e);
@@ -99,20 +124,90 @@ fn main() {
             }
         },
         "csv" => {
-            let csv = OutputFormatter::format_csv(&project_stats);
+            let csv = OutputFormatter::format_csv(project_stats, mixed_lines);
             println!("{}", csv);
         }
         _ => {
             // Default to table format
             let use_color = cli.should_use_color();
-            let table = OutputFormatter::format_table(&project_stats, use_color);
+            let table = OutputFormatter::format_table(project_stats, mixed_lines, use_color);
             println!("{}", table);
         }
     }
 }
 
-fn run_history_mode(cli: &Cli) {
-    // Use the first path (or current directory if none specified)
+/// Count lines of code in a revision's tree, read straight from the repository.
+fn run_revision_mode(cli: &Cli, revision: &str) {
+    let analyzer = open_repository(cli)
+        .paths(cli.paths.clone())
+        .exclude(cli.exclude.clone())
+        .include(cli.include.clone())
+        .hidden(cli.hidden);
+
+    if cli.verbose {
+        eprintln!("Analyzing files at {}", revision);
+    }
+
+    let project_stats = match analyzer.snapshot(revision) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: Failed to analyze revision {}: {}", revision, e);
+            process::exit(1);
+        }
+    };
+
+    print_project_stats(cli, &project_stats);
+}
+
+/// Attribute the code lines of the walked files to their authors.
+fn run_ownership_mode(cli: &Cli) {
+    let analyzer = open_repository(cli).group_by_email(cli.group_by_email);
+
+    let mut files = Vec::new();
+    for path in &cli.paths {
+        if cli.verbose {
+            eprintln!("Scanning: {}", path.display());
+        }
+
+        let walker = DirectoryWalker::new(path)
+            .hidden(cli.hidden)
+            .exclude(cli.exclude.clone())
+            .include(cli.include.clone());
+        files.extend(walker.walk());
+    }
+
+    let stats = match analyzer.ownership(&files, cli.verbose) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: Failed to blame files: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let format_lower = cli.format.to_lowercase();
+    match format_lower.as_str() {
+        "json" => match OutputFormatter::format_ownership_json(&stats) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error formatting JSON: {}", e);
+                process::exit(1);
+            }
+        },
+        "csv" => {
+            let csv = OutputFormatter::format_ownership_csv(&stats);
+            println!("{}", csv);
+        }
+        _ => {
+            let use_color = cli.should_use_color();
+            let output = OutputFormatter::format_ownership(&stats, use_color);
+            println!("{}", output);
+        }
+    }
+}
+
+/// Open the git repository containing the first path, exiting on failure.
+fn open_repository(cli: &Cli) -> GitAnalyzer {
+    // Use the first path (or current directory if none specified) to find the repository
     let path = cli.paths.first().expect("At least one path required");
 
     // Check if it's a git repository
@@ -122,14 +217,17 @@ fn run_history_mode(cli: &Cli) {
     }
 
     // Create GitAnalyzer
-    let analyzer = match GitAnalyzer::new(path) {
-        Ok(a) => a,
+    match GitAnalyzer::new(path) {
+        Ok(a) => a.mixed_lines(cli.mixed_lines().unwrap_or_default()),
         Err(e) => {
             eprintln!("Error: Failed to open git repository: {}", e);
             process::exit(1);
         }
-    };
+    }
+}
 
+/// Parse --since/--until/--last into a date range, exiting on invalid dates.
+fn parse_date_range(cli: &Cli) -> (Option<NaiveDateTime>, Option<NaiveDateTime>) {
     // Parse since and until dates
     let since = match cli.parse_since_date() {
         Ok(date) => date,
@@ -164,8 +262,81 @@ fn run_history_mode(cli: &Cli) {
         }
     }
 
+    (since, until)
+}
+
+/// Sample the size of the codebase over history.
+fn run_timeline_mode(cli: &Cli) {
+    let analyzer = open_repository(cli)
+        .paths(cli.paths.clone())
+        .exclude(cli.exclude.clone())
+        .include(cli.include.clone())
+        .hidden(cli.hidden)
+        .revision(cli.rev.clone())
+        .timezone(cli.timezone.unwrap_or_default())
+        .date_source(cli.date.unwrap_or_default());
+
+    let (since, until) = parse_date_range(cli);
+    let period = cli.period();
+
+    let points = match analyzer.timeline(period, since, until, cli.verbose) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Error: Failed to analyze git history: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let mixed_lines = cli.mixed_lines().unwrap_or_default();
+    let format_lower = cli.format.to_lowercase();
+    match format_lower.as_str() {
+        "json" => match OutputFormatter::format_timeline_json(&points, period.label()) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error formatting JSON: {}", e);
+                process::exit(1);
+            }
+        },
+        "csv" => {
+            let csv = OutputFormatter::format_timeline_csv(&points, period.label(), mixed_lines);
+            println!("{}", csv);
+        }
+        _ => {
+            let use_color = cli.should_use_color();
+            let output = OutputFormatter::format_timeline(
+                &points,
+                period.label(),
+                mixed_lines,
+                cli.row_limit(),
+                use_color,
+            );
+            println!("{}", output);
+        }
+    }
+}
+
+fn run_history_mode(cli: &Cli) {
+    let analyzer = open_repository(cli);
+
+    // Only count changes under the requested paths and patterns, by the requested authors
+    let analyzer = analyzer
+        .paths(cli.paths.clone())
+        .exclude(cli.exclude.clone())
+        .include(cli.include.clone())
+        .authors(cli.author_filter().unwrap_or_default())
+        .group_by_email(cli.group_by_email)
+        .co_author_credit(cli.co_author_credit.unwrap_or_default())
+        .merge_policy(cli.merges.unwrap_or_default())
+        .rename_threshold(cli.rename_threshold())
+        .revision(cli.rev.clone())
+        .all_branches(cli.all)
+        .timezone(cli.timezone.unwrap_or_default())
+        .date_source(cli.date.unwrap_or_default());
+
+    let (since, until) = parse_date_range(cli);
+
     // Analyze history
-    let stats = match analyzer.analyze_history(since, until, cli.verbose) {
+    let mut stats = match analyzer.analyze_history(since, until, cli.verbose) {
         Ok(s) => s,
         Err(e) => {
             eprintln!("Error: Failed to analyze git history: {}", e);
@@ -173,31 +344,23 @@ fn run_history_mode(cli: &Cli) {
         }
     };
 
-    // Filter by author if specified
-    let stats = if let Some(author_filter) = &cli.author {
-        // Filter daily stats and by_author to only include the specified author
-        let filtered_by_author = stats
-            .by_author
-            .into_iter()
-            .filter(|(name, _)| name.contains(author_filter))
-            .collect();
-
-        sniffy::git::HistoricalStats {
-            daily: stats.daily,
-            by_author: filtered_by_author,
-            total_commits: stats.total_commits,
-        }
-    } else {
-        stats
-    };
+    if let Some(count) = cli.hotspots {
+        if cli.weight_by_size {
+            analyzer.measure_hotspots(&mut stats);
+        }
+        print_hotspots(cli, &stats, count);
+        return;
+    }
 
-    // Aggregate by week if requested
-    let (time_series, period_label, limit) = if cli.by_week {
-        let weekly = stats.aggregate_by_week();
-        (weekly, "Weekly", Some(12)) // Show last 12 weeks by default
-    } else {
-        (stats.daily.clone(), "Daily", Some(30)) // Show last 30 days by default
-    };
+    // Aggregate into the requested period
+    let period = cli.period();
+    let time_series = stats.aggregate(period);
+    let (period_label, limit) = (period.label(), cli.row_limit());
+
+    if cli.by_language {
+        print_history_by_language(cli, &stats, &time_series, period_label, limit);
+        return;
+    }
 
     // Format and print results based on format option
     let format_lower = cli.format.to_lowercase();
@@ -227,3 +390,66 @@ fn run_history_mode(cli: &Cli) {
         }
     }
 }
+
+/// Print history split by language based on the format option.
+fn print_history_by_language(
+    cli: &Cli,
+    stats: &HistoricalStats,
+    time_series: &[DailyStats],
+    period_label: &str,
+    limit: Option<usize>,
+) {
+    let format_lower = cli.format.to_lowercase();
+    match format_lower.as_str() {
+        "json" => {
+            match OutputFormatter::format_history_by_language_json(stats, time_series, period_label)
+            {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Error formatting JSON: {}", e);
+                    process::exit(1);
+                }
+            }
+        }
+        "csv" => {
+            let csv =
+                OutputFormatter::format_history_by_language_csv(stats, time_series, period_label);
+            println!("{}", csv);
+        }
+        _ => {
+            let use_color = cli.should_use_color();
+            let output = OutputFormatter::format_history_by_language(
+                stats,
+                time_series,
+                period_label,
+                limit,
+                use_color,
+            );
+            println!("{}", output);
+        }
+    }
+}
+
+/// Print the files changed most often based on the format option.
+fn print_hotspots(cli: &Cli, stats: &HistoricalStats, count: usize) {
+    let format_lower = cli.format.to_lowercase();
+    match format_lower.as_str() {
+        "json" => match OutputFormatter::format_hotspots_json(stats, count, cli.weight_by_size) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error formatting JSON: {}", e);
+                process::exit(1);
+            }
+        },
+        "csv" => {
+            let csv = OutputFormatter::format_hotspots_csv(stats, count, cli.weight_by_size);
+            println!("{}", csv);
+        }
+        _ => {
+            let use_color = cli.should_use_color();
+            let output =
+                OutputFormatter::format_hotspots(stats, count, cli.weight_by_size, use_color);
+            println!("{}", output);
+        }
+    }
+}
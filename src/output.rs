@@ -3,10 +3,14 @@
 //! This module handles formatting statistics as tables
 //! and other output formats for the terminal.
 
-use crate::git::{DailyStats, HistoricalStats};
-use crate::stats::ProjectStats;
+use crate::git::{AuthorStats, DailyStats, FileChurn, HistoricalStats, LineChanges, TimelinePoint};
+use crate::ownership::{Ownership, OwnershipStats, CONCENTRATION_THRESHOLD};
+use crate::stats::{FileStats, MixedLines, ProjectStats};
+use chrono::NaiveDate;
 use comfy_table::{presets::UTF8_FULL, Cell, Color, ContentArrangement, Table};
 use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 /// Output formatter for displaying statistics.
 pub struct OutputFormatter;
@@ -14,8 +18,9 @@ pub struct OutputFormatter;
 impl OutputFormatter {
     /// Format project statistics as a table.
     ///
-    /// If `use_color` is false, colors will be disabled.
-    pub fn format_table(stats: &ProjectStats, use_color: bool) -> String {
+    /// Mixed lines get a column of their own if `mixed_lines` is
+    /// [`MixedLines::Separate`]. If `use_color` is false, colors will be disabled.
+    pub fn format_table(stats: &ProjectStats, mixed_lines: MixedLines, use_color: bool) -> String {
         let mut table = Table::new();
 
         // Set table style
@@ -23,8 +28,13 @@ impl OutputFormatter {
             .load_preset(UTF8_FULL)
             .set_content_arrangement(ContentArrangement::Dynamic);
 
+        let (total_files, total_stats) = stats.total();
+        let mixed = mixed_lines == MixedLines::Separate;
+
         // Add header
-        let header_cells = vec!["Language", "Files", "Blank", "Comment", "Code", "Total"];
+        let mut header_cells = vec!["Language", "Files"];
+        header_cells.extend(Self::line_count_headers(mixed));
+        header_cells.push("Total");
         if use_color {
             table.set_header(
                 header_cells
@@ -39,50 +49,88 @@ impl OutputFormatter {
         // Add rows for each language
         let languages = stats.get_languages();
         for lang_stats in &languages {
-            table.add_row(vec![
+            let counts = Self::line_counts(&lang_stats.stats, mixed)
+                .into_iter()
+                .chain([lang_stats.stats.total()]);
+            let mut row = vec![
                 Cell::new(&lang_stats.language),
                 Cell::new(Self::format_number(lang_stats.files)),
-                Cell::new(Self::format_number(lang_stats.stats.blank)),
-                Cell::new(Self::format_number(lang_stats.stats.comment)),
-                Cell::new(Self::format_number(lang_stats.stats.code)),
-                Cell::new(Self::format_number(lang_stats.stats.total())),
-            ]);
+            ];
+            row.extend(counts.map(|n| Cell::new(Self::format_number(n))));
+            table.add_row(row);
         }
 
         // Add total row
-        let (total_files, total_stats) = stats.total();
         if !languages.is_empty() {
-            // Create owned strings for the numbers
-            let total_files_str = Self::format_number(total_files);
-            let total_blank_str = Self::format_number(total_stats.blank);
-            let total_comment_str = Self::format_number(total_stats.comment);
-            let total_code_str = Self::format_number(total_stats.code);
-            let total_total_str = Self::format_number(total_stats.total());
-
-            if use_color {
-                table.add_row(vec![
-                    Cell::new("Total").fg(Color::Green),
-                    Cell::new(total_files_str).fg(Color::Green),
-                    Cell::new(total_blank_str).fg(Color::Green),
-                    Cell::new(total_comment_str).fg(Color::Green),
-                    Cell::new(total_code_str).fg(Color::Green),
-                    Cell::new(total_total_str).fg(Color::Green),
-                ]);
-            } else {
-                table.add_row(vec![
-                    Cell::new("Total"),
-                    Cell::new(total_files_str),
-                    Cell::new(total_blank_str),
-                    Cell::new(total_comment_str),
-                    Cell::new(total_code_str),
-                    Cell::new(total_total_str),
-                ]);
-            }
+            let numbers = std::iter::once(total_files)
+                .chain(Self::line_counts(&total_stats, mixed))
+                .chain([total_stats.total()])
+                .map(Self::format_number);
+            let row: Vec<_> = std::iter::once("Total".to_string())
+                .chain(numbers)
+                .map(|text| {
+                    if use_color {
+                        Cell::new(text).fg(Color::Green)
+                    } else {
+                        Cell::new(text)
+                    }
+                })
+                .collect();
+            table.add_row(row);
         }
 
         table.to_string()
     }
 
+    /// Get the headers of the line count columns.
+    ///
+    /// Mixed lines have a column of their own only if `mixed` is true.
+    fn line_count_headers(mixed: bool) -> Vec<&'static str> {
+        let mut headers = vec!["Blank", "Comment", "Doc", "Code"];
+        if mixed {
+            headers.push("Mixed");
+        }
+        headers
+    }
+
+    /// Get the line counts of some statistics, in the order of [`Self::line_count_headers`].
+    fn line_counts(stats: &FileStats, mixed: bool) -> Vec<usize> {
+        let mut counts = vec![stats.blank, stats.comment, stats.doc, stats.code];
+        if mixed {
+            counts.push(stats.mixed);
+        }
+        counts
+    }
+
+    /// Get the CSV header of the line count columns, followed by the total.
+    fn line_count_csv_header(mixed: bool) -> String {
+        let mut columns: Vec<_> = Self::line_count_headers(mixed)
+            .into_iter()
+            .map(str::to_lowercase)
+            .collect();
+        columns.push("total".to_string());
+        columns.join(",")
+    }
+
+    /// Get the CSV fields of some statistics' line counts, followed by the total.
+    fn line_count_csv_fields(stats: &FileStats, mixed: bool) -> String {
+        let mut fields: Vec<_> = Self::line_counts(stats, mixed)
+            .into_iter()
+            .map(|n| n.to_string())
+            .collect();
+        fields.push(stats.total().to_string());
+        fields.join(",")
+    }
+
+    /// Quote a CSV field if it contains a comma, quote or line break.
+    fn csv_field(value: &str) -> Cow<'_, str> {
+        if value.contains([',', '"', '\n', '\r']) {
+            Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+        } else {
+            Cow::Borrowed(value)
+        }
+    }
+
     /// Format a number with thousand separators.
     fn format_number(n: usize) -> String {
         let s = n.to_string();
@@ -101,7 +149,7 @@ impl OutputFormatter {
 
     /// Format a signed number with thousand separators and +/- sign.
     fn format_signed_number(n: i64) -> String {
-        let sign = if n >= 0 { "+" } else { "" };
+        let sign = if n >= 0 { "+" } else { "-" };
         let s = n.abs().to_string();
         let chars: Vec<char> = s.chars().collect();
         let mut result = String::new();
@@ -116,6 +164,28 @@ impl OutputFormatter {
         format!("{}{}", sign, result)
     }
 
+    /// Get the plural unit for a period label, such as "weeks" for "Weekly".
+    fn period_unit(period_label: &str) -> &'static str {
+        match period_label {
+            "Weekly" => "weeks",
+            "Monthly" => "months",
+            "Quarterly" => "quarters",
+            "Yearly" => "years",
+            _ => "days",
+        }
+    }
+
+    /// Format a percentage with one decimal.
+    fn format_percent(percent: f64) -> String {
+        format!("{:.1}%", percent)
+    }
+
+    /// Format an optional date, using "N/A" when missing.
+    fn format_optional_date(date: Option<NaiveDate>) -> String {
+        date.map(|d| d.to_string())
+            .unwrap_or_else(|| "N/A".to_string())
+    }
+
     /// Format git history statistics as a table.
     /// The `period_label` parameter can be "Daily" or "Weekly".
     ///
@@ -145,6 +215,21 @@ impl OutputFormatter {
                 .unwrap_or_else(|| "N/A".to_string())
         ));
 
+        if stats.renames > 0 || stats.copies > 0 {
+            output.push_str(&format!(
+                "Files Renamed: {}\nFiles Copied: {}\n\n",
+                Self::format_number(stats.renames),
+                Self::format_number(stats.copies)
+            ));
+        }
+
+        if stats.merges_skipped > 0 {
+            output.push_str(&format!(
+                "Merges Skipped: {}\n\n",
+                Self::format_number(stats.merges_skipped)
+            ));
+        }
+
         // Time series statistics table
         if !time_series.is_empty() {
             output.push_str(&format!("{} Statistics:\n", period_label));
@@ -154,7 +239,14 @@ impl OutputFormatter {
                 .load_preset(UTF8_FULL)
                 .set_content_arrangement(ContentArrangement::Dynamic);
 
-            let header_cells = vec!["Date", "Added", "Deleted", "Net Change"];
+            let header_cells = vec![
+                "Date",
+                "Added",
+                "Deleted",
+                "Doc Added",
+                "Doc Deleted",
+                "Net Change",
+            ];
             if use_color {
                 table.set_header(
                     header_cells
@@ -184,41 +276,24 @@ impl OutputFormatter {
                     net_cell
                 };
 
-                let added_cell = Cell::new(Self::format_number(daily.additions.code));
-                let deleted_cell = Cell::new(Self::format_number(daily.deletions.code));
-
-                let added_cell = if use_color {
-                    added_cell.fg(Color::Green)
-                } else {
-                    added_cell
-                };
-                let deleted_cell = if use_color {
-                    deleted_cell.fg(Color::Red)
-                } else {
-                    deleted_cell
-                };
-
-                table.add_row(vec![
-                    Cell::new(daily.date.to_string()),
-                    added_cell,
-                    deleted_cell,
-                    net_cell,
-                ]);
+                let mut row = vec![Cell::new(daily.date.to_string())];
+                row.extend(Self::change_cells(
+                    &daily.additions,
+                    &daily.deletions,
+                    use_color,
+                ));
+                row.push(net_cell);
+                table.add_row(row);
             }
 
             output.push_str(&table.to_string());
             output.push('\n');
 
             if time_series.len() > rows_to_show {
-                let period_name = if period_label == "Weekly" {
-                    "weeks"
-                } else {
-                    "days"
-                };
                 output.push_str(&format!(
                     "... and {} more {}\n\n",
                     time_series.len() - rows_to_show,
-                    period_name
+                    Self::period_unit(period_label)
                 ));
             }
         }
@@ -232,7 +307,19 @@ impl OutputFormatter {
                 .load_preset(UTF8_FULL)
                 .set_content_arrangement(ContentArrangement::Dynamic);
 
-            let author_header_cells = vec!["Author", "Code Lines", "Comments", "Total"];
+            let author_header_cells = vec![
+                "Author",
+                "Commits",
+                "Co-Authored",
+                "Added",
+                "Deleted",
+                "Doc Added",
+                "Doc Deleted",
+                "Net Change",
+                "Active Days",
+                "First Commit",
+                "Last Commit",
+            ];
             if use_color {
                 table.set_header(
                     author_header_cells
@@ -245,19 +332,21 @@ impl OutputFormatter {
             }
 
             // Sort authors by total lines contributed
-            let mut authors: Vec<_> = stats.by_author.iter().collect();
-            authors.sort_by(|a, b| {
-                let total_a = a.1.code + a.1.comment + a.1.blank;
-                let total_b = b.1.code + b.1.comment + b.1.blank;
-                total_b.cmp(&total_a)
-            });
+            let authors = stats.authors_by_contribution();
 
             for (author, author_stats) in authors.iter().take(10) {
                 table.add_row(vec![
                     Cell::new(author),
-                    Cell::new(Self::format_number(author_stats.code)),
-                    Cell::new(Self::format_number(author_stats.comment)),
-                    Cell::new(Self::format_number(author_stats.total())),
+                    Cell::new(Self::format_number(author_stats.commits)),
+                    Cell::new(Self::format_number(author_stats.co_authored_commits)),
+                    Cell::new(Self::format_number(author_stats.additions.code)),
+                    Cell::new(Self::format_number(author_stats.deletions.code)),
+                    Cell::new(Self::format_number(author_stats.additions.doc)),
+                    Cell::new(Self::format_number(author_stats.deletions.doc)),
+                    Cell::new(Self::format_signed_number(author_stats.net_code)),
+                    Cell::new(Self::format_number(author_stats.active_days)),
+                    Cell::new(Self::format_optional_date(author_stats.first_seen)),
+                    Cell::new(Self::format_optional_date(author_stats.last_seen)),
                 ]);
             }
 
@@ -267,6 +356,477 @@ impl OutputFormatter {
         output
     }
 
+    /// Format git history split by language as a table.
+    pub fn format_history_by_language(
+        stats: &HistoricalStats,
+        time_series: &[DailyStats],
+        period_label: &str,
+        limit: Option<usize>,
+        use_color: bool,
+    ) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!(
+            "Git History Analysis by Language\n\
+             Total Commits: {}\n\
+             Date Range: {} to {}\n\n",
+            Self::format_number(stats.total_commits),
+            time_series
+                .last()
+                .map(|d| d.date.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+            time_series
+                .first()
+                .map(|d| d.date.to_string())
+                .unwrap_or_else(|| "N/A".to_string())
+        ));
+
+        let totals = stats.language_totals();
+        if totals.is_empty() {
+            return output;
+        }
+
+        output.push_str("Language Totals:\n");
+        let mut table = Self::language_changes_table(&["Language"], use_color);
+        for (language, changes) in &totals {
+            table.add_row(Self::language_changes_row(
+                vec![Cell::new(language)],
+                changes,
+                use_color,
+            ));
+        }
+        output.push_str(&table.to_string());
+        output.push_str("\n\n");
+
+        output.push_str(&format!("{} Statistics by Language:\n", period_label));
+        let mut table = Self::language_changes_table(&["Date", "Language"], use_color);
+
+        let rows_to_show = limit.unwrap_or(time_series.len()).min(time_series.len());
+        for daily in time_series.iter().take(rows_to_show) {
+            for (language, changes) in Self::sorted_languages(daily) {
+                table.add_row(Self::language_changes_row(
+                    vec![Cell::new(daily.date.to_string()), Cell::new(language)],
+                    changes,
+                    use_color,
+                ));
+            }
+        }
+        output.push_str(&table.to_string());
+        output.push('\n');
+
+        if time_series.len() > rows_to_show {
+            output.push_str(&format!(
+                "... and {} more {}\n",
+                time_series.len() - rows_to_show,
+                Self::period_unit(period_label)
+            ));
+        }
+
+        output
+    }
+
+    /// Format code ownership as tables: top owners, then per directory and per file.
+    pub fn format_ownership(stats: &OwnershipStats, use_color: bool) -> String {
+        let mut output = String::new();
+
+        let total = stats.total();
+        output.push_str(&format!(
+            "Code Ownership\n\
+             Files: {}\n\
+             Code Lines: {}\n\
+             Bus Factor: {}\n",
+            Self::format_number(stats.files.len()),
+            Self::format_number(total.code),
+            Self::format_bus_factor(&total)
+        ));
+        if total.uncommitted > 0 {
+            output.push_str(&format!(
+                "Uncommitted Lines: {} ({})\n",
+                Self::format_number(total.uncommitted),
+                Self::format_percent(total.uncommitted_percent())
+            ));
+        }
+        output.push('\n');
+
+        if total.code == 0 {
+            return output;
+        }
+
+        output.push_str("Top Owners:\n");
+        let mut table = Self::header_table(&["Author", "Code Lines", "Share"], use_color);
+        for (author, lines) in total.authors_by_lines().into_iter().take(10) {
+            table.add_row(vec![
+                Cell::new(author),
+                Cell::new(Self::format_number(lines)),
+                Cell::new(Self::format_percent(total.percent(lines))),
+            ]);
+        }
+        output.push_str(&table.to_string());
+        output.push_str("\n\n");
+
+        let directories = stats.directories();
+        if !directories.is_empty() {
+            output.push_str("Ownership by Directory:\n");
+            output.push_str(&Self::ownership_table("Directory", &directories, use_color));
+            output.push_str("\n\n");
+
+            let concentrated: Vec<_> = directories
+                .iter()
+                .filter(|(_, ownership)| ownership.is_concentrated())
+                .map(|(path, _)| path.as_str())
+                .collect();
+            if !concentrated.is_empty() {
+                output.push_str(&format!(
+                    "Owned by a single author ({}% or more): {}\n\n",
+                    CONCENTRATION_THRESHOLD,
+                    concentrated.join(", ")
+                ));
+            }
+        }
+
+        output.push_str("Ownership by File:\n");
+        output.push_str(&Self::ownership_table("File", &stats.files, use_color));
+        output.push('\n');
+
+        output
+    }
+
+    /// Create a table with the given header cells.
+    fn header_table(headers: &[&str], use_color: bool) -> Table {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+
+        let header_cells: Vec<_> = headers
+            .iter()
+            .map(|h| {
+                if use_color {
+                    Cell::new(h).fg(Color::Cyan)
+                } else {
+                    Cell::new(h)
+                }
+            })
+            .collect();
+        table.set_header(header_cells);
+        table
+    }
+
+    /// Format a bus factor, using "N/A" when no code lines are committed.
+    fn format_bus_factor(ownership: &Ownership) -> String {
+        match ownership.bus_factor() {
+            0 => "N/A".to_string(),
+            n => Self::format_number(n),
+        }
+    }
+
+    /// Render one row per path with its top owner, share and bus factor.
+    ///
+    /// With colors, the share is red when a single author owns the path.
+    fn ownership_table(
+        kind: &str,
+        entries: &BTreeMap<String, Ownership>,
+        use_color: bool,
+    ) -> String {
+        let mut table = Self::header_table(
+            &[
+                kind,
+                "Code Lines",
+                "Authors",
+                "Top Owner",
+                "Share",
+                "Bus Factor",
+            ],
+            use_color,
+        );
+        for (path, ownership) in entries {
+            let authors = ownership.authors_by_lines();
+            let (owner, share) = match authors.first() {
+                Some((author, lines)) => (
+                    author.to_string(),
+                    Self::format_percent(ownership.percent(*lines)),
+                ),
+                None => ("N/A".to_string(), "N/A".to_string()),
+            };
+            let share_cell = Cell::new(share);
+            let share_cell = if use_color && ownership.is_concentrated() {
+                share_cell.fg(Color::Red)
+            } else {
+                share_cell
+            };
+            table.add_row(vec![
+                Cell::new(path),
+                Cell::new(Self::format_number(ownership.code)),
+                Cell::new(Self::format_number(authors.len())),
+                Cell::new(owner),
+                share_cell,
+                Cell::new(Self::format_bus_factor(ownership)),
+            ]);
+        }
+        table.to_string()
+    }
+
+    /// Format the files changed most often in git history as a table.
+    ///
+    /// Shows at most `count` files. If `weighted` is true, files are ranked by
+    /// commits times current lines of code, and those columns are shown too.
+    pub fn format_hotspots(
+        stats: &HistoricalStats,
+        count: usize,
+        weighted: bool,
+        use_color: bool,
+    ) -> String {
+        let mut output = String::new();
+
+        let hotspots = stats.hotspots(weighted);
+        output.push_str(&format!(
+            "Git History Hotspots\n\
+             Total Commits: {}\n\
+             Files Changed: {}\n\n",
+            Self::format_number(stats.total_commits),
+            Self::format_number(stats.by_file.len())
+        ));
+
+        if hotspots.is_empty() {
+            return output;
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+
+        let mut headers = vec![
+            "File",
+            "Commits",
+            "Authors",
+            "Added",
+            "Deleted",
+            "Doc Added",
+            "Doc Deleted",
+            "Churn",
+        ];
+        if weighted {
+            headers.extend(["Code Lines", "Score"]);
+        }
+        let header_cells: Vec<_> = headers
+            .into_iter()
+            .map(|h| {
+                if use_color {
+                    Cell::new(h).fg(Color::Cyan)
+                } else {
+                    Cell::new(h)
+                }
+            })
+            .collect();
+        table.set_header(header_cells);
+
+        for (path, churn) in hotspots.iter().take(count) {
+            let mut row = vec![
+                Cell::new(path),
+                Cell::new(Self::format_number(churn.commits)),
+                Cell::new(Self::format_number(churn.authors)),
+            ];
+            row.extend(Self::change_cells(
+                &churn.additions,
+                &churn.deletions,
+                use_color,
+            ));
+            row.push(Cell::new(Self::format_number(churn.churn())));
+            if weighted {
+                row.push(Cell::new(Self::format_number(
+                    churn.current_code.unwrap_or(0),
+                )));
+                row.push(Cell::new(Self::format_number(churn.weighted_score())));
+            }
+            table.add_row(row);
+        }
+        output.push_str(&table.to_string());
+        output.push('\n');
+
+        if hotspots.len() > count {
+            output.push_str(&format!("... and {} more files\n", hotspots.len() - count));
+        }
+
+        output
+    }
+
+    /// Create a table with the given leading columns followed by change columns.
+    fn language_changes_table(leading: &[&str], use_color: bool) -> Table {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+
+        let header_cells: Vec<_> = leading
+            .iter()
+            .chain(&["Added", "Deleted", "Doc Added", "Doc Deleted", "Net Change"])
+            .map(|h| {
+                if use_color {
+                    Cell::new(h).fg(Color::Cyan)
+                } else {
+                    Cell::new(h)
+                }
+            })
+            .collect();
+        table.set_header(header_cells);
+        table
+    }
+
+    /// Build a table row from leading cells and a language's code changes.
+    fn language_changes_row(
+        mut cells: Vec<Cell>,
+        changes: &LineChanges,
+        use_color: bool,
+    ) -> Vec<Cell> {
+        let net_code = changes.net_code();
+        let net_cell = Cell::new(Self::format_signed_number(net_code));
+
+        cells.extend(Self::change_cells(
+            &changes.additions,
+            &changes.deletions,
+            use_color,
+        ));
+        cells.push(match net_code {
+            n if n > 0 && use_color => net_cell.fg(Color::Green),
+            n if n < 0 && use_color => net_cell.fg(Color::Red),
+            _ => net_cell,
+        });
+        cells
+    }
+
+    /// Build the added and deleted cells of code, then documentation lines.
+    ///
+    /// With colors, additions are green and deletions red.
+    fn change_cells(additions: &FileStats, deletions: &FileStats, use_color: bool) -> Vec<Cell> {
+        [
+            (additions.code, Color::Green),
+            (deletions.code, Color::Red),
+            (additions.doc, Color::Green),
+            (deletions.doc, Color::Red),
+        ]
+        .into_iter()
+        .map(|(lines, color)| {
+            let cell = Cell::new(Self::format_number(lines));
+            if use_color {
+                cell.fg(color)
+            } else {
+                cell
+            }
+        })
+        .collect()
+    }
+
+    /// Get a period's per-language changes sorted by language name.
+    fn sorted_languages(daily: &DailyStats) -> Vec<(&String, &LineChanges)> {
+        let mut languages: Vec<_> = daily.by_language.iter().collect();
+        languages.sort_by(|a, b| a.0.cmp(b.0));
+        languages
+    }
+
+    /// Format a codebase size timeline as a table.
+    ///
+    /// Samples are expected most recent first, as returned by
+    /// [`GitAnalyzer::timeline`](crate::git::GitAnalyzer::timeline). Mixed lines
+    /// get a column of their own if `mixed_lines` is [`MixedLines::Separate`].
+    pub fn format_timeline(
+        points: &[TimelinePoint],
+        period_label: &str,
+        mixed_lines: MixedLines,
+        limit: Option<usize>,
+        use_color: bool,
+    ) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!(
+            "Codebase Timeline\n\
+             Samples: {}\n\
+             Date Range: {} to {}\n\n",
+            Self::format_number(points.len()),
+            points
+                .last()
+                .map(|p| p.date.to_string())
+                .unwrap_or_else(|| "N/A".to_string()),
+            points
+                .first()
+                .map(|p| p.date.to_string())
+                .unwrap_or_else(|| "N/A".to_string())
+        ));
+
+        if points.is_empty() {
+            return output;
+        }
+
+        output.push_str(&format!("{} Samples:\n", period_label));
+        let mut table = Table::new();
+
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+
+        let mixed = mixed_lines == MixedLines::Separate;
+        let mut header_cells = vec!["Date", "Commit", "Files"];
+        header_cells.extend(Self::line_count_headers(mixed));
+        header_cells.push("Code Change");
+        if use_color {
+            table.set_header(
+                header_cells
+                    .into_iter()
+                    .map(|h| Cell::new(h).fg(Color::Cyan))
+                    .collect::<Vec<_>>(),
+            );
+        } else {
+            table.set_header(header_cells);
+        }
+
+        let rows_to_show = limit.unwrap_or(points.len()).min(points.len());
+
+        for (i, point) in points.iter().enumerate().take(rows_to_show) {
+            let (files, totals) = point.stats.total();
+
+            // Change since the previous (older) sample
+            let change_cell = match points.get(i + 1) {
+                Some(older) => {
+                    let change = totals.code as i64 - older.stats.total().1.code as i64;
+                    let cell = Cell::new(Self::format_signed_number(change));
+                    if use_color && change > 0 {
+                        cell.fg(Color::Green)
+                    } else if use_color && change < 0 {
+                        cell.fg(Color::Red)
+                    } else {
+                        cell
+                    }
+                }
+                None => Cell::new(""),
+            };
+
+            let mut row = vec![
+                Cell::new(point.date.to_string()),
+                Cell::new(&point.commit[..point.commit.len().min(7)]),
+                Cell::new(Self::format_number(files)),
+            ];
+            row.extend(
+                Self::line_counts(&totals, mixed)
+                    .into_iter()
+                    .map(|n| Cell::new(Self::format_number(n))),
+            );
+            row.push(change_cell);
+            table.add_row(row);
+        }
+
+        output.push_str(&table.to_string());
+        output.push('\n');
+
+        if points.len() > rows_to_show {
+            output.push_str(&format!(
+                "... and {} more samples\n",
+                points.len() - rows_to_show
+            ));
+        }
+
+        output
+    }
+
     /// Format project statistics as JSON.
     pub fn format_json(stats: &ProjectStats) -> Result<String, serde_json::Error> {
         #[derive(Serialize)]
@@ -297,13 +857,19 @@ impl OutputFormatter {
         #[derive(Serialize)]
         struct JsonHistoryOutput {
             total_commits: usize,
+            merges_skipped: usize,
+            renames: usize,
+            copies: usize,
             period: String,
             time_series: Vec<DailyStats>,
-            by_author: std::collections::HashMap<String, crate::stats::FileStats>,
+            by_author: std::collections::HashMap<String, AuthorStats>,
         }
 
         let output = JsonHistoryOutput {
             total_commits: stats.total_commits,
+            merges_skipped: stats.merges_skipped,
+            renames: stats.renames,
+            copies: stats.copies,
             period: period_label.to_lowercase(),
             time_series: time_series.to_vec(),
             by_author: stats.by_author.clone(),
@@ -312,37 +878,249 @@ impl OutputFormatter {
         serde_json::to_string_pretty(&output)
     }
 
+    /// Format a codebase size timeline as JSON.
+    pub fn format_timeline_json(
+        points: &[TimelinePoint],
+        period_label: &str,
+    ) -> Result<String, serde_json::Error> {
+        #[derive(Serialize)]
+        struct JsonSample<'a> {
+            date: NaiveDate,
+            commit: &'a str,
+            languages: Vec<&'a crate::stats::LanguageStats>,
+            total_files: usize,
+            total_stats: crate::stats::FileStats,
+        }
+
+        #[derive(Serialize)]
+        struct JsonTimelineOutput<'a> {
+            period: String,
+            samples: Vec<JsonSample<'a>>,
+        }
+
+        let samples = points
+            .iter()
+            .map(|point| {
+                let (total_files, total_stats) = point.stats.total();
+                JsonSample {
+                    date: point.date,
+                    commit: &point.commit,
+                    languages: point.stats.get_languages(),
+                    total_files,
+                    total_stats,
+                }
+            })
+            .collect();
+
+        let output = JsonTimelineOutput {
+            period: period_label.to_lowercase(),
+            samples,
+        };
+
+        serde_json::to_string_pretty(&output)
+    }
+
+    /// Format git history split by language as JSON.
+    pub fn format_history_by_language_json(
+        stats: &HistoricalStats,
+        time_series: &[DailyStats],
+        period_label: &str,
+    ) -> Result<String, serde_json::Error> {
+        #[derive(Serialize)]
+        struct JsonLineChanges<'a> {
+            language: &'a str,
+            additions: crate::stats::FileStats,
+            deletions: crate::stats::FileStats,
+            net_code: i64,
+        }
+
+        #[derive(Serialize)]
+        struct JsonPeriod<'a> {
+            date: NaiveDate,
+            languages: Vec<JsonLineChanges<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonLanguageOutput<'a> {
+            total_commits: usize,
+            period: String,
+            languages: Vec<JsonLineChanges<'a>>,
+            time_series: Vec<JsonPeriod<'a>>,
+        }
+
+        fn entry<'a>(language: &'a str, changes: &LineChanges) -> JsonLineChanges<'a> {
+            JsonLineChanges {
+                language,
+                additions: changes.additions,
+                deletions: changes.deletions,
+                net_code: changes.net_code(),
+            }
+        }
+
+        let totals = stats.language_totals();
+        let output = JsonLanguageOutput {
+            total_commits: stats.total_commits,
+            period: period_label.to_lowercase(),
+            languages: totals
+                .iter()
+                .map(|(language, changes)| entry(language, changes))
+                .collect(),
+            time_series: time_series
+                .iter()
+                .map(|daily| JsonPeriod {
+                    date: daily.date,
+                    languages: Self::sorted_languages(daily)
+                        .into_iter()
+                        .map(|(language, changes)| entry(language, changes))
+                        .collect(),
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&output)
+    }
+
+    /// Format the files changed most often in git history as JSON.
+    pub fn format_hotspots_json(
+        stats: &HistoricalStats,
+        count: usize,
+        weighted: bool,
+    ) -> Result<String, serde_json::Error> {
+        #[derive(Serialize)]
+        struct JsonHotspot<'a> {
+            path: &'a str,
+            #[serde(flatten)]
+            churn: &'a FileChurn,
+            churn_code: usize,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            score: Option<usize>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonHotspotOutput<'a> {
+            total_commits: usize,
+            files_changed: usize,
+            weighted: bool,
+            hotspots: Vec<JsonHotspot<'a>>,
+        }
+
+        let output = JsonHotspotOutput {
+            total_commits: stats.total_commits,
+            files_changed: stats.by_file.len(),
+            weighted,
+            hotspots: stats
+                .hotspots(weighted)
+                .into_iter()
+                .take(count)
+                .map(|(path, churn)| JsonHotspot {
+                    path,
+                    churn,
+                    churn_code: churn.churn(),
+                    score: weighted.then(|| churn.weighted_score()),
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&output)
+    }
+
+    /// Format code ownership as JSON.
+    pub fn format_ownership_json(stats: &OwnershipStats) -> Result<String, serde_json::Error> {
+        #[derive(Serialize)]
+        struct JsonOwner<'a> {
+            author: &'a str,
+            code: usize,
+            percent: f64,
+        }
+
+        #[derive(Serialize)]
+        struct JsonOwnership<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            path: Option<&'a str>,
+            code: usize,
+            uncommitted: usize,
+            uncommitted_percent: f64,
+            bus_factor: usize,
+            concentrated: bool,
+            authors: Vec<JsonOwner<'a>>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonOwnershipOutput<'a> {
+            total: JsonOwnership<'a>,
+            directories: Vec<JsonOwnership<'a>>,
+            files: Vec<JsonOwnership<'a>>,
+        }
+
+        fn entry<'a>(path: Option<&'a str>, ownership: &'a Ownership) -> JsonOwnership<'a> {
+            JsonOwnership {
+                path,
+                code: ownership.code,
+                uncommitted: ownership.uncommitted,
+                uncommitted_percent: ownership.uncommitted_percent(),
+                bus_factor: ownership.bus_factor(),
+                concentrated: ownership.is_concentrated(),
+                authors: ownership
+                    .authors_by_lines()
+                    .into_iter()
+                    .map(|(author, lines)| JsonOwner {
+                        author,
+                        code: lines,
+                        percent: ownership.percent(lines),
+                    })
+                    .collect(),
+            }
+        }
+
+        let total = stats.total();
+        let directories = stats.directories();
+        let output = JsonOwnershipOutput {
+            total: entry(None, &total),
+            directories: directories
+                .iter()
+                .map(|(path, ownership)| entry(Some(path), ownership))
+                .collect(),
+            files: stats
+                .files
+                .iter()
+                .map(|(path, ownership)| entry(Some(path), ownership))
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&output)
+    }
+
     /// Format project statistics as CSV.
-    pub fn format_csv(stats: &ProjectStats) -> String {
+    ///
+    /// The `mixed` column is present if `mixed_lines` is [`MixedLines::Separate`].
+    pub fn format_csv(stats: &ProjectStats, mixed_lines: MixedLines) -> String {
         let mut output = String::new();
+        let (total_files, total_stats) = stats.total();
+        let mixed = mixed_lines == MixedLines::Separate;
 
         // Header
-        output.push_str("language,files,blank,comment,code,total\n");
+        output.push_str(&format!(
+            "language,files,{}\n",
+            Self::line_count_csv_header(mixed)
+        ));
 
         // Data rows
         let languages = stats.get_languages();
         for lang_stats in &languages {
             output.push_str(&format!(
-                "{},{},{},{},{},{}\n",
+                "{},{},{}\n",
                 lang_stats.language,
                 lang_stats.files,
-                lang_stats.stats.blank,
-                lang_stats.stats.comment,
-                lang_stats.stats.code,
-                lang_stats.stats.total()
+                Self::line_count_csv_fields(&lang_stats.stats, mixed)
             ));
         }
 
         // Total row
-        let (total_files, total_stats) = stats.total();
         if !languages.is_empty() {
             output.push_str(&format!(
-                "Total,{},{},{},{},{}\n",
+                "Total,{},{}\n",
                 total_files,
-                total_stats.blank,
-                total_stats.comment,
-                total_stats.code,
-                total_stats.total()
+                Self::line_count_csv_fields(&total_stats, mixed)
             ));
         }
 
@@ -362,16 +1140,23 @@ impl OutputFormatter {
             "# Git History Analysis - {} Statistics\n",
             period_label
         ));
-        output.push_str(&format!("# Total Commits: {}\n\n", stats.total_commits));
+        output.push_str(&format!("# Total Commits: {}\n", stats.total_commits));
+        output.push_str(&format!("# Merges Skipped: {}\n", stats.merges_skipped));
+        output.push_str(&format!("# Files Renamed: {}\n", stats.renames));
+        output.push_str(&format!("# Files Copied: {}\n\n", stats.copies));
 
         // Time series data
-        output.push_str("date,additions_code,deletions_code,net_change\n");
+        output.push_str(
+            "date,additions_code,deletions_code,additions_doc,deletions_doc,net_change\n",
+        );
         for daily in time_series {
             output.push_str(&format!(
-                "{},{},{},{}\n",
+                "{},{},{},{},{},{}\n",
                 daily.date,
                 daily.additions.code,
                 daily.deletions.code,
+                daily.additions.doc,
+                daily.deletions.doc,
                 if daily.net_code >= 0 {
                     format!("+{}", daily.net_code)
                 } else {
@@ -383,24 +1168,205 @@ impl OutputFormatter {
         // Author statistics
         if !stats.by_author.is_empty() {
             output.push_str("\n# Top Contributors\n");
-            output.push_str("author,code_lines,comments,total\n");
+            output.push_str(
+                "author,commits,co_authored_commits,additions_code,deletions_code,additions_doc,deletions_doc,net_code,active_days,first_seen,last_seen\n",
+            );
 
-            let mut authors: Vec<_> = stats.by_author.iter().collect();
-            authors.sort_by(|a, b| {
-                let total_a = a.1.code + a.1.comment + a.1.blank;
-                let total_b = b.1.code + b.1.comment + b.1.blank;
-                total_b.cmp(&total_a)
-            });
+            let authors = stats.authors_by_contribution();
 
             for (author, author_stats) in authors.iter().take(10) {
                 output.push_str(&format!(
-                    "{},{},{},{}\n",
-                    author,
-                    author_stats.code,
-                    author_stats.comment,
-                    author_stats.total()
+                    "{},{},{},{},{},{},{},{},{},{},{}\n",
+                    Self::csv_field(author),
+                    author_stats.commits,
+                    author_stats.co_authored_commits,
+                    author_stats.additions.code,
+                    author_stats.deletions.code,
+                    author_stats.additions.doc,
+                    author_stats.deletions.doc,
+                    author_stats.net_code,
+                    author_stats.active_days,
+                    author_stats
+                        .first_seen
+                        .map(|d| d.to_string())
+                        .unwrap_or_default(),
+                    author_stats
+                        .last_seen
+                        .map(|d| d.to_string())
+                        .unwrap_or_default()
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Format a codebase size timeline as CSV, one row per sample and language.
+    ///
+    /// The `mixed` column is present if `mixed_lines` is [`MixedLines::Separate`].
+    pub fn format_timeline_csv(
+        points: &[TimelinePoint],
+        period_label: &str,
+        mixed_lines: MixedLines,
+    ) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!("# Codebase Timeline - {} Samples\n", period_label));
+        output.push_str(&format!("# Samples: {}\n\n", points.len()));
+
+        let mixed = mixed_lines == MixedLines::Separate;
+        output.push_str(&format!(
+            "date,commit,language,files,{}\n",
+            Self::line_count_csv_header(mixed)
+        ));
+        for point in points {
+            for lang_stats in point.stats.get_languages() {
+                output.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    point.date,
+                    point.commit,
+                    lang_stats.language,
+                    lang_stats.files,
+                    Self::line_count_csv_fields(&lang_stats.stats, mixed)
                 ));
             }
+
+            let (total_files, total_stats) = point.stats.total();
+            output.push_str(&format!(
+                "{},{},Total,{},{}\n",
+                point.date,
+                point.commit,
+                total_files,
+                Self::line_count_csv_fields(&total_stats, mixed)
+            ));
+        }
+
+        output
+    }
+
+    /// Format git history split by language as CSV.
+    pub fn format_history_by_language_csv(
+        stats: &HistoricalStats,
+        time_series: &[DailyStats],
+        period_label: &str,
+    ) -> String {
+        let mut output = String::new();
+
+        output.push_str(&format!(
+            "# Git History Analysis by Language - {} Statistics\n",
+            period_label
+        ));
+        output.push_str(&format!("# Total Commits: {}\n\n", stats.total_commits));
+
+        output.push_str(
+            "date,language,additions_code,deletions_code,additions_doc,deletions_doc,net_change\n",
+        );
+        for daily in time_series {
+            for (language, changes) in Self::sorted_languages(daily) {
+                output.push_str(&format!(
+                    "{},{},{},{},{},{},{}\n",
+                    daily.date,
+                    language,
+                    changes.additions.code,
+                    changes.deletions.code,
+                    changes.additions.doc,
+                    changes.deletions.doc,
+                    if changes.net_code() >= 0 {
+                        format!("+{}", changes.net_code())
+                    } else {
+                        changes.net_code().to_string()
+                    }
+                ));
+            }
+        }
+
+        output
+    }
+
+    /// Format code ownership as CSV, one row per author of each path.
+    ///
+    /// The bus factor and concentration flag of the path are repeated on each
+    /// of its rows. Authors' percentages are shares of the committed lines.
+    /// Uncommitted lines get an empty author, and a share of all code lines.
+    /// The whole repository is listed with the scope "total" and an empty path.
+    pub fn format_ownership_csv(stats: &OwnershipStats) -> String {
+        let mut output = String::new();
+
+        output.push_str("scope,path,author,code,percent,bus_factor,concentrated\n");
+
+        let mut push_rows = |scope: &str, path: &str, ownership: &Ownership| {
+            let (bus_factor, concentrated) = (ownership.bus_factor(), ownership.is_concentrated());
+            for (author, lines) in ownership.authors_by_lines() {
+                output.push_str(&format!(
+                    "{},{},{},{},{:.1},{},{}\n",
+                    scope,
+                    Self::csv_field(path),
+                    Self::csv_field(author),
+                    lines,
+                    ownership.percent(lines),
+                    bus_factor,
+                    concentrated
+                ));
+            }
+            if ownership.uncommitted > 0 {
+                output.push_str(&format!(
+                    "{},{},,{},{:.1},{},{}\n",
+                    scope,
+                    Self::csv_field(path),
+                    ownership.uncommitted,
+                    ownership.uncommitted_percent(),
+                    bus_factor,
+                    concentrated
+                ));
+            }
+        };
+
+        push_rows("total", "", &stats.total());
+        for (path, ownership) in &stats.directories() {
+            push_rows("directory", path, ownership);
+        }
+        for (path, ownership) in &stats.files {
+            push_rows("file", path, ownership);
+        }
+
+        output
+    }
+
+    /// Format the files changed most often in git history as CSV.
+    pub fn format_hotspots_csv(stats: &HistoricalStats, count: usize, weighted: bool) -> String {
+        let mut output = String::new();
+
+        output.push_str("# Git History Hotspots\n");
+        output.push_str(&format!("# Total Commits: {}\n\n", stats.total_commits));
+
+        output.push_str(
+            "file,commits,authors,additions_code,deletions_code,additions_doc,deletions_doc,churn",
+        );
+        if weighted {
+            output.push_str(",current_code,score");
+        }
+        output.push('\n');
+
+        for (path, churn) in stats.hotspots(weighted).into_iter().take(count) {
+            output.push_str(&format!(
+                "{},{},{},{},{},{},{},{}",
+                Self::csv_field(path),
+                churn.commits,
+                churn.authors,
+                churn.additions.code,
+                churn.deletions.code,
+                churn.additions.doc,
+                churn.deletions.doc,
+                churn.churn()
+            ));
+            if weighted {
+                output.push_str(&format!(
+                    ",{},{}",
+                    churn.current_code.unwrap_or(0),
+                    churn.weighted_score()
+                ));
+            }
+            output.push('\n');
         }
 
         output
@@ -425,10 +1391,297 @@ mod tests {
         assert_eq!(OutputFormatter::format_number(1234567), "1,234,567");
     }
 
+    #[test]
+    fn test_format_signed_number() {
+        assert_eq!(OutputFormatter::format_signed_number(0), "+0");
+        assert_eq!(OutputFormatter::format_signed_number(1234), "+1,234");
+        assert_eq!(OutputFormatter::format_signed_number(-1234), "-1,234");
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(OutputFormatter::csv_field("Alice"), "Alice");
+        assert_eq!(OutputFormatter::csv_field("Doe, Jane"), "\"Doe, Jane\"");
+        assert_eq!(
+            OutputFormatter::csv_field("Jane \"JD\" Doe"),
+            "\"Jane \"\"JD\"\" Doe\""
+        );
+        assert_eq!(OutputFormatter::csv_field("a\nb"), "\"a\nb\"");
+    }
+
+    #[test]
+    fn test_format_history_author_columns() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let mut author = AuthorStats::default();
+        author.record_commit(
+            date,
+            FileStats {
+                blank: 0,
+                comment: 0,
+                doc: 30,
+                code: 1200,
+                mixed: 0,
+            },
+            FileStats {
+                blank: 0,
+                comment: 0,
+                doc: 4,
+                code: 200,
+                mixed: 0,
+            },
+        );
+
+        let mut stats = HistoricalStats::default();
+        stats.by_author.insert("Doe, Jane".to_string(), author);
+
+        let table = OutputFormatter::format_history(&stats, &[], "Daily", None, false);
+        assert!(table.contains("Active Days"));
+        assert!(table.contains("Doc Added"));
+        assert!(table.contains("1,200"));
+        assert!(table.contains("+1,000"));
+        assert!(table.contains("2024-03-01"));
+
+        let csv = OutputFormatter::format_history_csv(&stats, &[], "Daily");
+        assert!(csv.contains("additions_code,deletions_code,additions_doc,deletions_doc,net_code"));
+        assert!(csv.contains("\"Doe, Jane\",1,0,1200,200,30,4,1000,1,2024-03-01,2024-03-01"));
+    }
+
+    #[test]
+    fn test_format_timeline() {
+        let sample = |day: u32, commit: &str, code: usize| {
+            let mut stats = ProjectStats::new();
+            stats.add_file_stats(
+                "Rust",
+                FileStats {
+                    blank: 1,
+                    comment: 2,
+                    doc: 3,
+                    code,
+                    mixed: 0,
+                },
+            );
+            TimelinePoint {
+                date: NaiveDate::from_ymd_opt(2024, 3, day).unwrap(),
+                commit: commit.to_string(),
+                stats,
+            }
+        };
+        let points = vec![
+            sample(2, "bbbbbbbbbbbb", 1500),
+            sample(1, "aaaaaaaaaaaa", 1000),
+        ];
+
+        let table =
+            OutputFormatter::format_timeline(&points, "Daily", MixedLines::Code, None, false);
+        assert!(table.contains("Samples: 2"));
+        assert!(table.contains("Date Range: 2024-03-01 to 2024-03-02"));
+        assert!(table.contains("bbbbbbb"));
+        assert!(table.contains("1,500"));
+        assert!(table.contains("+500"));
+
+        let csv = OutputFormatter::format_timeline_csv(&points, "Daily", MixedLines::Code);
+        assert!(csv.contains("date,commit,language,files,blank,comment,doc,code,total"));
+        assert!(csv.contains("2024-03-02,bbbbbbbbbbbb,Rust,1,1,2,3,1500,1506"));
+        assert!(csv.contains("2024-03-01,aaaaaaaaaaaa,Total,1,1,2,3,1000,1006"));
+
+        let json = OutputFormatter::format_timeline_json(&points, "Daily").unwrap();
+        assert!(json.contains("\"period\": \"daily\""));
+        assert!(json.contains("\"commit\": \"aaaaaaaaaaaa\""));
+    }
+
+    #[test]
+    fn test_format_history_by_language() {
+        let changes = |added: usize, deleted: usize| LineChanges {
+            additions: FileStats {
+                blank: 0,
+                comment: 0,
+                doc: added / 100,
+                code: added,
+                mixed: 0,
+            },
+            deletions: FileStats {
+                blank: 0,
+                comment: 0,
+                doc: deleted / 100,
+                code: deleted,
+                mixed: 0,
+            },
+        };
+        let mut daily = DailyStats {
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            ..DailyStats::default()
+        };
+        daily
+            .by_language
+            .insert("Rust".to_string(), changes(1200, 200));
+        daily.by_language.insert("YAML".to_string(), changes(3, 10));
+
+        let stats = HistoricalStats {
+            daily: vec![daily],
+            total_commits: 1,
+            ..HistoricalStats::default()
+        };
+
+        let table =
+            OutputFormatter::format_history_by_language(&stats, &stats.daily, "Daily", None, false);
+        assert!(table.contains("Language Totals"));
+        assert!(table.contains("Doc Deleted"));
+        assert!(table.contains("+1,000"));
+        assert!(table.contains("-7"));
+
+        let csv = OutputFormatter::format_history_by_language_csv(&stats, &stats.daily, "Daily");
+        assert!(csv.contains("2024-03-01,Rust,1200,200,12,2,+1000"));
+        assert!(csv.contains("2024-03-01,YAML,3,10,0,0,-7"));
+
+        let json = OutputFormatter::format_history_by_language_json(&stats, &stats.daily, "Daily")
+            .unwrap();
+        assert!(json.contains("\"language\": \"YAML\""));
+        assert!(json.contains("\"net_code\": -7"));
+    }
+
+    #[test]
+    fn test_format_hotspots() {
+        let churn = |commits: usize, added: usize, current_code: Option<usize>| {
+            let mut churn = FileChurn::default();
+            churn.commits = commits;
+            churn.authors = 1;
+            churn.additions.code = added;
+            churn.additions.doc = commits;
+            churn.current_code = current_code;
+            churn
+        };
+        let mut stats = HistoricalStats {
+            total_commits: 7,
+            ..HistoricalStats::default()
+        };
+        stats
+            .by_file
+            .insert("src/busy.rs".to_string(), churn(5, 40, Some(10)));
+        stats
+            .by_file
+            .insert("src/big.rs".to_string(), churn(2, 900, Some(800)));
+        stats
+            .by_file
+            .insert("src/gone.rs".to_string(), churn(1, 5, None));
+
+        let table = OutputFormatter::format_hotspots(&stats, 2, false, false);
+        assert!(table.contains("Doc Added"));
+        assert!(table.contains("src/busy.rs"));
+        assert!(table.contains("src/big.rs"));
+        assert!(!table.contains("src/gone.rs"));
+        assert!(table.contains("... and 1 more files"));
+        assert!(table.find("src/busy.rs").unwrap() < table.find("src/big.rs").unwrap());
+
+        let weighted = OutputFormatter::format_hotspots(&stats, 10, true, false);
+        assert!(weighted.contains("Score"));
+        assert!(weighted.contains("1,600"));
+        assert!(!weighted.contains("src/gone.rs"));
+        assert!(weighted.find("src/big.rs").unwrap() < weighted.find("src/busy.rs").unwrap());
+
+        let csv = OutputFormatter::format_hotspots_csv(&stats, 10, false);
+        assert!(csv.contains(
+            "file,commits,authors,additions_code,deletions_code,additions_doc,deletions_doc,churn\n"
+        ));
+        assert!(csv.contains("src/busy.rs,5,1,40,0,5,0,40\n"));
+        assert!(csv.contains("src/gone.rs,1,1,5,0,1,0,5\n"));
+
+        let json = OutputFormatter::format_hotspots_json(&stats, 1, true).unwrap();
+        assert!(json.contains("\"path\": \"src/big.rs\""));
+        assert!(json.contains("\"score\": 1600"));
+        assert!(!json.contains("src/busy.rs"));
+    }
+
+    #[test]
+    fn test_format_ownership() {
+        let mut lib = Ownership::default();
+        lib.add_lines("Alice", 30);
+        lib.add_lines("Bob", 10);
+        let mut main = Ownership::default();
+        main.add_lines("Bob", 10);
+        main.add_uncommitted(10);
+
+        let mut stats = OwnershipStats::default();
+        stats.files.insert("src/lib.rs".to_string(), lib);
+        stats.files.insert("main.rs".to_string(), main);
+
+        let table = OutputFormatter::format_ownership(&stats, false);
+        assert!(table.contains("Code Lines: 60"));
+        assert!(table.contains("Uncommitted Lines: 10 (16.7%)"));
+        assert!(table.contains("Ownership by Directory"));
+        assert!(table.contains("75.0%"));
+        assert!(table.contains("60.0%"));
+        assert!(table.contains("Bus Factor: 1"));
+        assert!(!table.contains("Owned by a single author"));
+
+        let csv = OutputFormatter::format_ownership_csv(&stats);
+        assert!(csv.starts_with("scope,path,author,code,percent,bus_factor,concentrated\n"));
+        // Authors' shares are of committed lines, so they agree with the flag
+        assert!(csv.contains("total,,Alice,30,60.0,1,false\n"));
+        assert!(csv.contains("total,,Bob,20,40.0,1,false\n"));
+        assert!(csv.contains("total,,,10,16.7,1,false\n"));
+        assert!(csv.contains("directory,src,Alice,30,75.0,1,false\n"));
+        assert!(csv.contains("file,main.rs,Bob,10,100.0,1,true\n"));
+        assert!(csv.contains("file,main.rs,,10,50.0,1,true\n"));
+
+        let json = OutputFormatter::format_ownership_json(&stats).unwrap();
+        assert!(json.contains("\"path\": \"src/lib.rs\""));
+        assert!(json.contains("\"uncommitted\": 10"));
+        assert!(json.contains("\"uncommitted_percent\": 50.0"));
+        assert!(json.contains("\"percent\": 75.0"));
+        assert!(json.contains("\"bus_factor\": 1"));
+
+        // A directory written by a single author is flagged
+        let mut solo = Ownership::default();
+        solo.add_lines("Carol", 100);
+        stats.files.insert("vendor/lib.rs".to_string(), solo);
+        let table = OutputFormatter::format_ownership(&stats, false);
+        assert!(table.contains("Owned by a single author (90% or more): vendor"));
+        let json = OutputFormatter::format_ownership_json(&stats).unwrap();
+        assert!(json.contains("\"concentrated\": true"));
+    }
+
+    #[test]
+    fn test_format_mixed_lines_column() {
+        let mut stats = ProjectStats::new();
+        let file = FileStats {
+            blank: 1,
+            comment: 2,
+            doc: 0,
+            code: 3,
+            mixed: 0,
+        };
+        stats.add_file_stats("Rust", file);
+
+        let csv = OutputFormatter::format_csv(&stats, MixedLines::Code);
+        assert!(csv.contains("language,files,blank,comment,doc,code,total\n"));
+        let table = OutputFormatter::format_table(&stats, MixedLines::Code, false);
+        assert!(!table.contains("Mixed"));
+
+        // Mixed lines counted on their own get a column, even when there are none
+        let csv = OutputFormatter::format_csv(&stats, MixedLines::Separate);
+        assert!(csv.contains("language,files,blank,comment,doc,code,mixed,total\n"));
+        assert!(csv.contains("Rust,1,1,2,0,3,0,6\n"));
+        let table = OutputFormatter::format_table(&stats, MixedLines::Separate, false);
+        assert!(table.contains("Mixed"));
+
+        stats.add_file_stats("Rust", FileStats { mixed: 4, ..file });
+        let csv = OutputFormatter::format_csv(&stats, MixedLines::Separate);
+        assert!(csv.contains("Rust,2,2,4,0,6,4,16\n"));
+
+        let points = vec![TimelinePoint {
+            date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            commit: "aaaaaaaaaaaa".to_string(),
+            stats: ProjectStats::new(),
+        }];
+        let csv = OutputFormatter::format_timeline_csv(&points, "Daily", MixedLines::Separate);
+        assert!(csv.contains("date,commit,language,files,blank,comment,doc,code,mixed,total\n"));
+        assert!(csv.contains("2024-03-01,aaaaaaaaaaaa,Total,0,0,0,0,0,0,0\n"));
+    }
+
     #[test]
     fn test_format_table_empty() {
         let stats = ProjectStats::new();
-        let table = OutputFormatter::format_table(&stats, true);
+        let table = OutputFormatter::format_table(&stats, MixedLines::Code, true);
 
         // Should have header but no data rows
         assert!(table.contains("Language"));
@@ -442,8 +1695,10 @@ mod tests {
             "Rust",
             FileStats {
                 blank: 10,
-                comment: 20,
+                comment: 12,
+                doc: 8,
                 code: 70,
+                mixed: 0,
             },
         );
         stats.add_file_stats(
@@ -451,16 +1706,19 @@ mod tests {
             FileStats {
                 blank: 5,
                 comment: 10,
+                doc: 0,
                 code: 35,
+                mixed: 0,
             },
         );
 
-        let table = OutputFormatter::format_table(&stats, true);
+        let table = OutputFormatter::format_table(&stats, MixedLines::Code, true);
 
         // Check for language names
         assert!(table.contains("Rust") || table.contains("Python"));
         // Check for Total row
         assert!(table.contains("Total"));
+        assert!(table.contains("Doc"));
         // Check for some numbers
         assert!(table.contains("70"));
         assert!(table.contains("35"));
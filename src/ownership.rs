@@ -0,0 +1,226 @@
+//! Code ownership statistics.
+//!
+//! This module holds the data structures for attributing today's code lines
+//! to the authors who last changed them, as computed by
+//! [`GitAnalyzer::ownership`](crate::git::GitAnalyzer::ownership).
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Share of code lines, in percent, from which a single author is considered
+/// to own a file or directory on their own.
+pub const CONCENTRATION_THRESHOLD: f64 = 90.0;
+
+/// Code lines attributed to authors, for a file, a directory or a whole repository.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Ownership {
+    /// Total code lines, including uncommitted ones.
+    pub code: usize,
+    /// Code lines that are not committed yet.
+    pub uncommitted: usize,
+    /// Code lines last changed by each author.
+    pub by_author: HashMap<String, usize>,
+}
+
+impl Ownership {
+    /// Attribute code lines to an author.
+    pub fn add_lines(&mut self, author: &str, lines: usize) {
+        self.code += lines;
+        *self.by_author.entry(author.to_string()).or_insert(0) += lines;
+    }
+
+    /// Record code lines that are not committed yet.
+    pub fn add_uncommitted(&mut self, lines: usize) {
+        self.code += lines;
+        self.uncommitted += lines;
+    }
+
+    /// Merge another ownership into this one.
+    pub fn merge(&mut self, other: &Ownership) {
+        self.code += other.code;
+        self.uncommitted += other.uncommitted;
+        for (author, lines) in &other.by_author {
+            *self.by_author.entry(author.clone()).or_insert(0) += lines;
+        }
+    }
+
+    /// Get authors sorted by owned lines (descending), then by name.
+    pub fn authors_by_lines(&self) -> Vec<(&String, usize)> {
+        let mut authors: Vec<_> = self
+            .by_author
+            .iter()
+            .map(|(author, lines)| (author, *lines))
+            .collect();
+        authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        authors
+    }
+
+    /// Get the number of code lines owned by authors, leaving out uncommitted ones.
+    pub fn committed(&self) -> usize {
+        self.code - self.uncommitted
+    }
+
+    /// Get the bus factor: the fewest authors who together own more than half
+    /// of the committed code lines.
+    ///
+    /// Returns 0 if there are no committed code lines.
+    pub fn bus_factor(&self) -> usize {
+        let mut owned = 0;
+        for (count, (_, lines)) in self.authors_by_lines().into_iter().enumerate() {
+            owned += lines;
+            if owned * 2 > self.committed() {
+                return count + 1;
+            }
+        }
+        0
+    }
+
+    /// Check if a single author owns at least [`CONCENTRATION_THRESHOLD`]
+    /// percent of the committed code lines.
+    pub fn is_concentrated(&self) -> bool {
+        self.authors_by_lines()
+            .first()
+            .is_some_and(|(_, lines)| self.percent(*lines) >= CONCENTRATION_THRESHOLD)
+    }
+
+    /// Get the share of committed code lines, in percent, that a number of
+    /// lines owned by authors represents.
+    pub fn percent(&self, lines: usize) -> f64 {
+        if self.committed() == 0 {
+            0.0
+        } else {
+            lines as f64 * 100.0 / self.committed() as f64
+        }
+    }
+
+    /// Get the share of all code lines, in percent, that are not committed yet.
+    pub fn uncommitted_percent(&self) -> f64 {
+        if self.code == 0 {
+            0.0
+        } else {
+            self.uncommitted as f64 * 100.0 / self.code as f64
+        }
+    }
+}
+
+/// Code ownership of the files in a working tree.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OwnershipStats {
+    /// Ownership per file, keyed by repository-relative path.
+    pub files: BTreeMap<String, Ownership>,
+}
+
+impl OwnershipStats {
+    /// Ownership of every file combined.
+    pub fn total(&self) -> Ownership {
+        let mut total = Ownership::default();
+        for ownership in self.files.values() {
+            total.merge(ownership);
+        }
+        total
+    }
+
+    /// Ownership per directory, keyed by repository-relative path.
+    ///
+    /// Each directory includes the files of all its subdirectories. Files at
+    /// the repository root only count towards the [`total`](Self::total).
+    pub fn directories(&self) -> BTreeMap<String, Ownership> {
+        let mut directories: BTreeMap<String, Ownership> = BTreeMap::new();
+        for (path, ownership) in &self.files {
+            for dir in Path::new(path).ancestors().skip(1) {
+                if dir.as_os_str().is_empty() {
+                    break;
+                }
+                directories
+                    .entry(dir.to_string_lossy().replace('\\', "/"))
+                    .or_default()
+                    .merge(ownership);
+            }
+        }
+        directories
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ownership(authors: &[(&str, usize)]) -> Ownership {
+        let mut ownership = Ownership::default();
+        for (author, lines) in authors {
+            ownership.add_lines(author, *lines);
+        }
+        ownership
+    }
+
+    #[test]
+    fn test_ownership_authors_by_lines() {
+        let mut file = ownership(&[("Bob", 10), ("Alice", 30), ("Carol", 10)]);
+        file.add_uncommitted(50);
+
+        assert_eq!(file.code, 100);
+        assert_eq!(file.percent(30), 60.0);
+        assert_eq!(file.uncommitted_percent(), 50.0);
+        let authors = file.authors_by_lines();
+        assert_eq!(authors[0], (&"Alice".to_string(), 30));
+        assert_eq!(authors[1].0, "Bob");
+        assert_eq!(authors[2].0, "Carol");
+        assert_eq!(Ownership::default().percent(0), 0.0);
+        assert_eq!(Ownership::default().uncommitted_percent(), 0.0);
+    }
+
+    #[test]
+    fn test_ownership_bus_factor() {
+        // Alice alone owns exactly half, which is not more than half
+        let mut shared = ownership(&[("Alice", 50), ("Bob", 30), ("Carol", 20)]);
+        assert_eq!(shared.bus_factor(), 2);
+        assert!(!shared.is_concentrated());
+
+        shared.add_lines("Alice", 1);
+        assert_eq!(shared.bus_factor(), 1);
+
+        let solo = ownership(&[("Alice", 95), ("Bob", 5)]);
+        assert_eq!(solo.bus_factor(), 1);
+        assert!(solo.is_concentrated());
+
+        // Uncommitted lines aren't owned by anyone, so they don't count
+        let mut uncommitted = ownership(&[("Alice", 95), ("Bob", 5)]);
+        uncommitted.add_uncommitted(500);
+        assert_eq!(uncommitted.committed(), 100);
+        assert_eq!(uncommitted.bus_factor(), 1);
+        assert!(uncommitted.is_concentrated());
+
+        let mut local = Ownership::default();
+        local.add_uncommitted(10);
+        assert_eq!(local.bus_factor(), 0);
+        assert!(!local.is_concentrated());
+        assert_eq!(Ownership::default().bus_factor(), 0);
+        assert!(!Ownership::default().is_concentrated());
+    }
+
+    #[test]
+    fn test_ownership_directories() {
+        let mut stats = OwnershipStats::default();
+        stats
+            .files
+            .insert("build.rs".to_string(), ownership(&[("Alice", 5)]));
+        stats
+            .files
+            .insert("src/lib.rs".to_string(), ownership(&[("Alice", 10)]));
+        stats.files.insert(
+            "src/git/blame.rs".to_string(),
+            ownership(&[("Bob", 20), ("Alice", 5)]),
+        );
+
+        let directories = stats.directories();
+        assert_eq!(directories.len(), 2);
+        assert_eq!(directories["src"].code, 35);
+        assert_eq!(directories["src"].by_author["Alice"], 15);
+        assert_eq!(directories["src/git"].code, 25);
+
+        let total = stats.total();
+        assert_eq!(total.code, 40);
+        assert_eq!(total.by_author["Alice"], 20);
+    }
+}
@@ -4,8 +4,8 @@
 //! and coordinating line classification.
 
 use crate::classifier::classify_file;
-use crate::language::LanguageDetector;
-use crate::stats::FileStats;
+use crate::language::{LanguageDetector, LanguageInfo};
+use crate::stats::{FileStats, MixedLines};
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
@@ -31,6 +31,7 @@ pub fn is_binary_file(path: &Path) -> std::io::Result<bool> {
 /// File processor that analyzes files and returns statistics.
 pub struct FileProcessor {
     detector: LanguageDetector,
+    mixed_lines: MixedLines,
 }
 
 impl FileProcessor {
@@ -38,9 +39,16 @@ impl FileProcessor {
     pub fn new() -> Self {
         Self {
             detector: LanguageDetector::new(),
+            mixed_lines: MixedLines::default(),
         }
     }
 
+    /// Set how lines with both code and comments are counted.
+    pub fn mixed_lines(mut self, policy: MixedLines) -> Self {
+        self.mixed_lines = policy;
+        self
+    }
+
     /// Process a file and return its language and statistics.
     ///
     /// Returns None if:
@@ -64,32 +72,57 @@ impl FileProcessor {
             Err(_) => return None,
         };
 
-        let reader = BufReader::new(file);
-        let mut lines = Vec::new();
-
-        // Read lines, handling UTF-8 errors gracefully
-        for (line_num, line_result) in reader.lines().enumerate() {
-            match line_result {
-                Ok(line) => lines.push(line),
-                Err(_) => {
-                    // Skip invalid UTF-8 lines
-                    // In a real implementation, we might log this
-                    eprintln!(
-                        "Warning: Skipping line {} in {} due to encoding error",
-                        line_num + 1,
-                        path.display()
-                    );
-                }
-            }
+        let stats = classify_reader(BufReader::new(file), path, language);
+        let stats = self.mixed_lines.apply(stats);
+
+        Some((language.name.to_string(), stats))
+    }
+
+    /// Process in-memory file contents and return their language and statistics.
+    ///
+    /// The path is only used for language detection and warnings, so this works
+    /// for content that isn't on disk, such as blobs from a git tree.
+    ///
+    /// Returns None if the content is binary or the language is not recognized.
+    pub fn process_bytes(&self, path: &Path, content: &[u8]) -> Option<(String, FileStats)> {
+        // Same heuristic as is_binary_file: null bytes in the first 8KB
+        if content[..content.len().min(8192)].contains(&0) {
+            return None;
         }
 
-        // Classify the file
-        let stats = classify_file(&lines, language);
+        let language = self.detector.detect_from_path(path)?;
+        let stats = self
+            .mixed_lines
+            .apply(classify_reader(content, path, language));
 
         Some((language.name.to_string(), stats))
     }
 }
 
+/// Read lines from a reader and classify them.
+fn classify_reader<R: BufRead>(reader: R, path: &Path, language: &LanguageInfo) -> FileStats {
+    let mut lines = Vec::new();
+
+    // Read lines, handling UTF-8 errors gracefully
+    for (line_num, line_result) in reader.lines().enumerate() {
+        match line_result {
+            Ok(line) => lines.push(line),
+            Err(_) => {
+                // Skip invalid UTF-8 lines
+                // In a real implementation, we might log this
+                eprintln!(
+                    "Warning: Skipping line {} in {} due to encoding error",
+                    line_num + 1,
+                    path.display()
+                );
+            }
+        }
+    }
+
+    // Classify the file
+    classify_file(&lines, language)
+}
+
 impl Default for FileProcessor {
     fn default() -> Self {
         Self::new()
@@ -144,7 +177,7 @@ mod tests {
 
         // Write a simple Rust file
         writeln!(temp_file, "// This is a comment").unwrap();
-        writeln!(temp_file, "").unwrap();
+        writeln!(temp_file).unwrap();
         writeln!(temp_file, "fn main() {{").unwrap();
         writeln!(temp_file, "    let x = 5;").unwrap();
         writeln!(temp_file, "}}").unwrap();
@@ -229,4 +262,41 @@ mod tests {
         // Cleanup
         std::fs::remove_file(&temp_path).ok();
     }
+
+    #[test]
+    fn test_process_bytes() {
+        let processor = FileProcessor::new();
+        let content = b"// comment\n\nfn main() {\n    let x = 5;\n}\n";
+
+        let (language, stats) = processor
+            .process_bytes(Path::new("src/main.rs"), content)
+            .unwrap();
+        assert_eq!(language, "Rust");
+        assert_eq!(stats.blank, 1);
+        assert_eq!(stats.comment, 1);
+        assert_eq!(stats.code, 3);
+
+        // Binary content and unknown extensions are skipped
+        assert!(processor
+            .process_bytes(Path::new("main.rs"), &[0x89, 0x50, 0x00, 0x0A])
+            .is_none());
+        assert!(processor
+            .process_bytes(Path::new("notes.unknown"), b"text\n")
+            .is_none());
+    }
+
+    #[test]
+    fn test_process_bytes_mixed_lines() {
+        let content = b"let x = 5; // five\nlet y = 6;\n";
+        let path = Path::new("main.rs");
+
+        let (_, stats) = FileProcessor::new().process_bytes(path, content).unwrap();
+        assert_eq!((stats.code, stats.mixed), (2, 0));
+
+        let (_, stats) = FileProcessor::new()
+            .mixed_lines(MixedLines::Separate)
+            .process_bytes(path, content)
+            .unwrap();
+        assert_eq!((stats.code, stats.mixed), (1, 1));
+    }
 }
@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ops::{Add, AddAssign};
+use std::str::FromStr;
 
 /// Statistics for a single file or aggregated files.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -14,8 +15,12 @@ pub struct FileStats {
     pub blank: usize,
     /// Number of comment lines.
     pub comment: usize,
+    /// Number of documentation comment lines.
+    pub doc: usize,
     /// Number of code lines.
     pub code: usize,
+    /// Number of lines with both code and comments, when counted on their own.
+    pub mixed: usize,
 }
 
 impl FileStats {
@@ -26,7 +31,7 @@ impl FileStats {
 
     /// Calculate the total number of lines.
     pub fn total(&self) -> usize {
-        self.blank + self.comment + self.code
+        self.blank + self.comment + self.doc + self.code + self.mixed
     }
 }
 
@@ -37,7 +42,9 @@ impl Add for FileStats {
         Self {
             blank: self.blank + other.blank,
             comment: self.comment + other.comment,
+            doc: self.doc + other.doc,
             code: self.code + other.code,
+            mixed: self.mixed + other.mixed,
         }
     }
 }
@@ -46,7 +53,66 @@ impl AddAssign for FileStats {
     fn add_assign(&mut self, other: Self) {
         self.blank += other.blank;
         self.comment += other.comment;
+        self.doc += other.doc;
         self.code += other.code;
+        self.mixed += other.mixed;
+    }
+}
+
+/// How lines with both code and comments are counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MixedLines {
+    /// Count mixed lines as code.
+    #[default]
+    Code,
+    /// Count mixed lines as comments.
+    Comment,
+    /// Count mixed lines as both code and comments, so totals count them twice.
+    Both,
+    /// Count mixed lines on their own.
+    Separate,
+}
+
+impl MixedLines {
+    /// Fold the mixed lines of `stats` into the counts this policy asks for.
+    pub fn apply(self, stats: FileStats) -> FileStats {
+        let mixed = stats.mixed;
+        match self {
+            MixedLines::Code => FileStats {
+                code: stats.code + mixed,
+                mixed: 0,
+                ..stats
+            },
+            MixedLines::Comment => FileStats {
+                comment: stats.comment + mixed,
+                mixed: 0,
+                ..stats
+            },
+            MixedLines::Both => FileStats {
+                code: stats.code + mixed,
+                comment: stats.comment + mixed,
+                mixed: 0,
+                ..stats
+            },
+            MixedLines::Separate => stats,
+        }
+    }
+}
+
+impl FromStr for MixedLines {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "code" => Ok(MixedLines::Code),
+            "comment" => Ok(MixedLines::Comment),
+            "both" => Ok(MixedLines::Both),
+            "separate" => Ok(MixedLines::Separate),
+            _ => Err(format!(
+                "Invalid mixed lines policy '{}'. Supported values: code, comment, both, separate",
+                s
+            )),
+        }
     }
 }
 
@@ -136,6 +202,7 @@ mod tests {
         let stats = FileStats::new();
         assert_eq!(stats.blank, 0);
         assert_eq!(stats.comment, 0);
+        assert_eq!(stats.doc, 0);
         assert_eq!(stats.code, 0);
     }
 
@@ -143,8 +210,10 @@ mod tests {
     fn test_file_stats_total() {
         let stats = FileStats {
             blank: 10,
-            comment: 20,
+            comment: 15,
+            doc: 5,
             code: 70,
+            mixed: 0,
         };
         assert_eq!(stats.total(), 100);
     }
@@ -154,16 +223,21 @@ mod tests {
         let stats1 = FileStats {
             blank: 10,
             comment: 20,
+            doc: 4,
             code: 30,
+            mixed: 0,
         };
         let stats2 = FileStats {
             blank: 5,
             comment: 15,
+            doc: 2,
             code: 25,
+            mixed: 0,
         };
         let result = stats1 + stats2;
         assert_eq!(result.blank, 15);
         assert_eq!(result.comment, 35);
+        assert_eq!(result.doc, 6);
         assert_eq!(result.code, 55);
     }
 
@@ -172,12 +246,16 @@ mod tests {
         let mut stats1 = FileStats {
             blank: 10,
             comment: 20,
+            doc: 0,
             code: 30,
+            mixed: 0,
         };
         let stats2 = FileStats {
             blank: 5,
             comment: 15,
+            doc: 0,
             code: 25,
+            mixed: 0,
         };
         stats1 += stats2;
         assert_eq!(stats1.blank, 15);
@@ -185,6 +263,33 @@ mod tests {
         assert_eq!(stats1.code, 55);
     }
 
+    #[test]
+    fn test_mixed_lines_apply() {
+        let stats = FileStats {
+            blank: 1,
+            comment: 2,
+            doc: 3,
+            code: 4,
+            mixed: 5,
+        };
+
+        let code = MixedLines::Code.apply(stats);
+        assert_eq!((code.comment, code.code, code.mixed), (2, 9, 0));
+        assert_eq!(code.total(), stats.total());
+
+        let comment = MixedLines::Comment.apply(stats);
+        assert_eq!((comment.comment, comment.code, comment.mixed), (7, 4, 0));
+
+        // Counting mixed lines as both code and comments counts them twice
+        let both = MixedLines::Both.apply(stats);
+        assert_eq!((both.comment, both.code, both.mixed), (7, 9, 0));
+        assert_eq!(both.total(), stats.total() + 5);
+
+        assert_eq!(MixedLines::Separate.apply(stats), stats);
+        assert_eq!("BOTH".parse::<MixedLines>(), Ok(MixedLines::Both));
+        assert!("half".parse::<MixedLines>().is_err());
+    }
+
     #[test]
     fn test_project_stats_new() {
         let stats = ProjectStats::new();
@@ -199,7 +304,9 @@ mod tests {
             FileStats {
                 blank: 10,
                 comment: 20,
+                doc: 0,
                 code: 70,
+                mixed: 0,
             },
         );
         project.add_file_stats(
@@ -207,7 +314,9 @@ mod tests {
             FileStats {
                 blank: 5,
                 comment: 10,
+                doc: 0,
                 code: 35,
+                mixed: 0,
             },
         );
 
@@ -226,7 +335,9 @@ mod tests {
             FileStats {
                 blank: 10,
                 comment: 20,
+                doc: 0,
                 code: 70,
+                mixed: 0,
             },
         );
         project.add_file_stats(
@@ -234,7 +345,9 @@ mod tests {
             FileStats {
                 blank: 5,
                 comment: 10,
+                doc: 0,
                 code: 35,
+                mixed: 0,
             },
         );
 
@@ -24,7 +24,7 @@ pub struct DirectoryWalker {
 /// - Python cache directories (__pycache__, .venv, venv)
 /// - Minified files (*.min.js, *.min.css)
 /// - Lock files (package-lock.json, Cargo.lock, etc.)
-fn should_skip_file(path: &Path) -> bool {
+pub(crate) fn should_skip_file(path: &Path) -> bool {
     // Skip directories by checking path components
     for component in path.components() {
         if let Some(component_str) = component.as_os_str().to_str() {
@@ -180,7 +180,7 @@ mod tests {
 
         // Initialize git repository (required for .gitignore to work)
         std::process::Command::new("git")
-            .args(&["init"])
+            .args(["init"])
             .current_dir(temp_dir.path())
             .output()
             .expect("Failed to initialize git repo");
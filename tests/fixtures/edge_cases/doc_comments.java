@@ -0,0 +1,13 @@
+/**
+ * A greeter.
+ */
+public class Greeter {
+    /* Not documentation */
+    private String name;
+
+    /** Returns the greeting. */
+    public String greet() {
+        // Plain comment
+        return "Hello, " + name; /** trailing */
+    }
+}
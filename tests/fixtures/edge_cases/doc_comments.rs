@@ -0,0 +1,19 @@
+//! Crate-level documentation.
+//! Spans two lines.
+
+/// Adds two numbers.
+///
+/// Returns their sum.
+pub fn add(a: i32, b: i32) -> i32 {
+    // An ordinary comment
+    a + b
+}
+
+//// Four slashes make an ordinary comment.
+/**
+ * Block documentation.
+ */
+pub struct Point;
+
+/*! Inner block documentation. */
+/**/
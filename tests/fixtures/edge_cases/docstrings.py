@@ -0,0 +1,23 @@
+"""Module docstring."""
+
+QUERY = """
+SELECT name
+FROM users
+"""
+
+
+class Repository:
+    """
+    Class docstring.
+    """
+
+    def find(self, cursor, name):
+        """Method docstring."""
+        cursor.execute(
+            """
+            SELECT * FROM users WHERE name = ?
+            """,
+            (name,),
+        )
+        message = f"""Found {name}"""
+        return message
@@ -0,0 +1,14 @@
+{- Haskell block comments nest
+   {- so this inner comment -}
+   does not end the outer one
+-}
+main :: IO ()
+main = do
+  {- {- -} -} putStrLn "hello" -- trailing comment
+  pure ()
+
+-- Expected counts:
+-- Blank: 1 (line 9)
+-- Comment: 9 (lines 1-4, 10-14)
+-- Code: 4 (lines 5-8)
+-- Total: 14
@@ -9,7 +9,7 @@ fn main() {
 }
 
 // Expected counts:
-// Blank: 1
-// Comment: 6 (lines 1, 4-7, line 11 onwards)
+// Blank: 2 (lines 2, 10)
+// Comment: 10 (lines 1, 4-7, 11-15)
 // Code: 3 (lines 3, 8, 9)
-// Total: 10
+// Total: 15
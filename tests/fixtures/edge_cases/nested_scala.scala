@@ -0,0 +1,13 @@
+/* Scala block comments nest
+ * /* inner
+ *  */
+ */
+object Main extends App {
+  println("Hello") /* trailing /* nested */ */
+}
+
+// Expected counts:
+// Blank: 1 (line 8)
+// Comment: 9 (lines 1-4, 9-13)
+// Code: 3 (lines 5-7)
+// Total: 13
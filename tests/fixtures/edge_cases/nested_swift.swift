@@ -0,0 +1,14 @@
+/* Swift block comments nest
+   /* inner */
+   still a comment
+*/
+func greet() {
+    /* /* nested */ */
+    print("/* not a comment */")
+}
+
+// Expected counts:
+// Blank: 1 (line 9)
+// Comment: 10 (lines 1-4, 6, 10-14)
+// Code: 3 (lines 5, 7, 8)
+// Total: 14
@@ -0,0 +1,9 @@
+# Comment markers inside strings are not comments
+s = "# not a comment"
+t = 'it\'s # still a string'
+query = """
+# part of the string
+"""
+
+# a real comment
+print(s, t, query)
@@ -0,0 +1,21 @@
+// Comment markers inside strings are not comments
+fn main() {
+    let url = "http://example.com/*";
+    let pattern = r#"// "quoted" /* raw */"#;
+    let quote = '"';
+
+    let message = "escaped \" /* still a string";
+    /* a real comment */
+    println!("{} {} {} {}", url, pattern, quote, message);
+    let long = "first line
+    // second line of the string
+    last line";
+}
+
+// Lifetimes and loop labels are not char literals
+fn first<'a>(words: &'a [&'a str]) -> &'a str {
+    let newline = '\n';
+    'outer: loop { break 'outer; }
+    words.first().copied().unwrap_or("it's empty")
+}
+// a comment after the lifetimes
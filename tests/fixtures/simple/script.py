@@ -14,7 +14,7 @@ if __name__ == "__main__":
     print(greet("World"))
 
 # Expected counts:
-# Blank: 3
-# Comment: 6 (shebang is code, but # comments and docstring)
-# Code: 6
-# Total: 15
+# Blank: 4
+# Comment: 7 (including these counts; shebang is code), Doc: 4 (docstring)
+# Code: 5
+# Total: 20
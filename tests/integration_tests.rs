@@ -15,7 +15,7 @@ fn fixture_path(name: &str) -> PathBuf {
 
 #[test]
 fn test_simple_rust_file() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple/main.rs"));
 
     cmd.assert()
@@ -26,7 +26,7 @@ fn test_simple_rust_file() {
 
 #[test]
 fn test_multi_lang_directory() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("multi_lang"));
 
     cmd.assert()
@@ -38,7 +38,7 @@ fn test_multi_lang_directory() {
 
 #[test]
 fn test_simple_directory_totals() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple"));
 
     cmd.assert()
@@ -49,7 +49,7 @@ fn test_simple_directory_totals() {
 
 #[test]
 fn test_binary_file_skipped() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("edge_cases"));
 
     cmd.assert()
@@ -59,7 +59,7 @@ fn test_binary_file_skipped() {
 
 #[test]
 fn test_json_output_format() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple/main.rs"))
         .arg("--format")
         .arg("json");
@@ -75,7 +75,7 @@ fn test_json_output_format() {
 
 #[test]
 fn test_csv_output_format() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple/main.rs"))
         .arg("--format")
         .arg("csv");
@@ -83,7 +83,7 @@ fn test_csv_output_format() {
     cmd.assert()
         .success()
         .stdout(predicate::str::contains(
-            "language,files,blank,comment,code,total",
+            "language,files,blank,comment,doc,code,total",
         ))
         .stdout(predicate::str::contains("Rust"));
 }
@@ -96,7 +96,7 @@ fn test_hidden_files_excluded_by_default() {
     fs::write(temp_dir.path().join("regular.rs"), "fn main() {}").unwrap();
     fs::write(temp_dir.path().join(".hidden.rs"), "fn test() {}").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     cmd.assert().success().stdout(predicate::str::contains("1")); // Should show 1 file
@@ -110,7 +110,7 @@ fn test_hidden_files_included_with_flag() {
     fs::write(temp_dir.path().join("regular.rs"), "fn main() {}").unwrap();
     fs::write(temp_dir.path().join(".hidden.rs"), "fn test() {}").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path()).arg("--hidden");
 
     cmd.assert().success().stdout(predicate::str::contains("2"));
@@ -118,7 +118,7 @@ fn test_hidden_files_included_with_flag() {
 
 #[test]
 fn test_invalid_path_error() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg("/nonexistent/path/that/does/not/exist");
 
     cmd.assert().failure().stderr(
@@ -130,7 +130,7 @@ fn test_invalid_path_error() {
 fn test_empty_directory() {
     let temp_dir = TempDir::new().unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     // Should succeed but report no files
@@ -139,7 +139,7 @@ fn test_empty_directory() {
 
 #[test]
 fn test_verbose_mode() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple")).arg("--verbose");
 
     cmd.assert()
@@ -149,7 +149,7 @@ fn test_verbose_mode() {
 
 #[test]
 fn test_parallel_jobs_option() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple")).arg("--jobs").arg("2");
 
     cmd.assert().success();
@@ -167,7 +167,7 @@ fn test_node_modules_skipped() {
     // Create regular file
     fs::write(temp_dir.path().join("app.js"), "console.log('hi');").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     cmd.assert().success().stdout(predicate::str::contains("1"));
@@ -184,7 +184,7 @@ fn test_minified_files_skipped() {
     )
     .unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     cmd.assert().success().stdout(predicate::str::contains("1"));
@@ -197,7 +197,7 @@ fn test_lock_files_skipped() {
     fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
     fs::write(temp_dir.path().join("package-lock.json"), "{}").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     cmd.assert().success().stdout(predicate::str::contains("1"));
@@ -223,7 +223,7 @@ fn test_permission_denied_directory() {
     perms.set_mode(0o000);
     fs::set_permissions(&forbidden_dir, perms).unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     // Should succeed but skip the forbidden directory
@@ -246,7 +246,7 @@ fn test_multiple_paths() {
     fs::write(temp_dir1.path().join("file1.rs"), "fn main() {}").unwrap();
     fs::write(temp_dir2.path().join("file2.py"), "def main(): pass").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir1.path()).arg(temp_dir2.path());
 
     cmd.assert()
@@ -258,7 +258,7 @@ fn test_multiple_paths() {
 
 #[test]
 fn test_exact_counts_simple_rust() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple/main.rs"))
         .arg("--format")
         .arg("json");
@@ -274,7 +274,7 @@ fn test_exact_counts_simple_rust() {
 
 #[test]
 fn test_exact_counts_multi_lang() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("multi_lang"))
         .arg("--format")
         .arg("csv");
@@ -288,7 +288,7 @@ fn test_exact_counts_multi_lang() {
 
 #[test]
 fn test_parallel_jobs_zero() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple")).arg("--jobs").arg("0"); // Auto-detect CPUs
 
     cmd.assert().success();
@@ -296,7 +296,7 @@ fn test_parallel_jobs_zero() {
 
 #[test]
 fn test_parallel_jobs_one() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple")).arg("--jobs").arg("1"); // Single-threaded
 
     cmd.assert().success();
@@ -305,7 +305,7 @@ fn test_parallel_jobs_one() {
 #[test]
 fn test_git_history_on_git_repo() {
     // Test on the sniffy repo itself
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".").arg("--history");
 
     let output = cmd.assert().success();
@@ -316,7 +316,7 @@ fn test_git_history_on_git_repo() {
 
 #[test]
 fn test_git_history_with_since() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--since")
@@ -327,7 +327,7 @@ fn test_git_history_with_since() {
 
 #[test]
 fn test_git_history_by_week() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".").arg("--history").arg("--by-week");
 
     cmd.assert().success();
@@ -335,7 +335,7 @@ fn test_git_history_by_week() {
 
 #[test]
 fn test_git_history_json_format() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".").arg("--history").arg("--format").arg("json");
 
     let output = cmd.assert().success();
@@ -347,7 +347,7 @@ fn test_git_history_json_format() {
 
 #[test]
 fn test_git_history_csv_format() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".").arg("--history").arg("--format").arg("csv");
 
     let output = cmd.assert().success();
@@ -365,7 +365,7 @@ fn test_binary_file_actually_skipped() {
     let binary_path = temp_dir.path().join("binary.bin");
     fs::write(&binary_path, b"\x00\x01\x02\x03\x04\x05").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     cmd.assert().success().stdout(predicate::str::contains("1")); // Only 1 file (the .rs file)
@@ -373,7 +373,7 @@ fn test_binary_file_actually_skipped() {
 
 #[test]
 fn test_edge_case_only_comments() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("edge_cases/only_comments.rs"))
         .arg("--format")
         .arg("json");
@@ -385,9 +385,119 @@ fn test_edge_case_only_comments() {
     assert!(stdout.contains("\"code\":0") || stdout.contains("\"code\": 0"));
 }
 
+#[test]
+fn test_edge_case_comment_markers_in_strings() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(fixture_path("edge_cases/strings.rs"))
+        .arg(fixture_path("edge_cases/strings.py"))
+        .arg("--format")
+        .arg("csv");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rust,1,2,4,0,15,21"))
+        .stdout(predicate::str::contains("Python,1,1,2,0,6,9"));
+}
+
+#[test]
+fn test_edge_case_nested_comments() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(fixture_path("edge_cases/nested_rust.rs"))
+        .arg(fixture_path("edge_cases/nested_haskell.hs"))
+        .arg(fixture_path("edge_cases/nested_swift.swift"))
+        .arg(fixture_path("edge_cases/nested_kotlin.kt"))
+        .arg(fixture_path("edge_cases/nested_scala.scala"))
+        .arg(fixture_path("edge_cases/nested_comments.c"))
+        .arg("--format")
+        .arg("csv");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rust,1,2,10,0,3,15"))
+        .stdout(predicate::str::contains("Haskell,1,1,9,0,4,14"))
+        .stdout(predicate::str::contains("Swift,1,1,10,0,3,14"))
+        .stdout(predicate::str::contains("Kotlin,1,1,9,0,3,13"))
+        .stdout(predicate::str::contains("Scala,1,1,9,0,3,13"))
+        .stdout(predicate::str::contains("C,1,2,9,0,4,15"));
+}
+
+#[test]
+fn test_edge_case_doc_comments() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(fixture_path("edge_cases/doc_comments.rs"))
+        .arg(fixture_path("edge_cases/doc_comments.java"))
+        .arg(fixture_path("simple/script.py"))
+        .arg("--format")
+        .arg("csv");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rust,1,3,3,9,4,19"))
+        .stdout(predicate::str::contains("Java,1,1,2,4,6,13"))
+        .stdout(predicate::str::contains("Python,1,4,7,4,5,20"));
+}
+
+#[test]
+fn test_edge_case_python_docstrings() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(fixture_path("edge_cases/docstrings.py"))
+        .arg("--format")
+        .arg("csv");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Python,1,4,0,5,14,23"));
+}
+
+#[test]
+fn test_mixed_lines_policy() {
+    let count = |policy: &str| {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+        cmd.arg(fixture_path("edge_cases/mixed.rs"))
+            .arg("--mixed-lines")
+            .arg(policy)
+            .arg("--format")
+            .arg("csv");
+        cmd.assert()
+    };
+
+    count("code")
+        .success()
+        .stdout(predicate::str::contains("Rust,1,3,6,0,4,13"));
+    count("comment")
+        .success()
+        .stdout(predicate::str::contains("Rust,1,3,10,0,0,13"));
+    count("both")
+        .success()
+        .stdout(predicate::str::contains("Rust,1,3,10,0,4,17"));
+    count("separate")
+        .success()
+        .stdout(predicate::str::contains(
+            "language,files,blank,comment,doc,code,mixed,total",
+        ))
+        .stdout(predicate::str::contains("Rust,1,3,6,0,0,4,13"));
+    count("half")
+        .failure()
+        .stderr(predicate::str::contains("Invalid mixed lines policy"));
+
+    // The column doesn't depend on whether any mixed lines were found
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(fixture_path("edge_cases/only_code.rs"))
+        .arg("--mixed-lines")
+        .arg("separate")
+        .arg("--format")
+        .arg("csv");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "language,files,blank,comment,doc,code,mixed,total",
+        ))
+        .stdout(predicate::str::contains("Rust,1,0,0,0,5,0,5"));
+}
+
 #[test]
 fn test_edge_case_only_code() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("edge_cases/only_code.rs"))
         .arg("--format")
         .arg("json");
@@ -401,7 +511,7 @@ fn test_edge_case_only_code() {
 
 #[test]
 fn test_verbose_shows_file_count() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple")).arg("--verbose");
 
     let output = cmd.assert().success();
@@ -410,7 +520,7 @@ fn test_verbose_shows_file_count() {
 
 #[test]
 fn test_table_format_explicit() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(fixture_path("simple/main.rs"))
         .arg("--format")
         .arg("table");
@@ -430,7 +540,7 @@ fn test_nested_directories() {
     fs::write(nested.join("helper.rs"), "fn help() {}").unwrap();
     fs::write(temp_dir.path().join("main.rs"), "fn main() {}").unwrap();
 
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(temp_dir.path());
 
     cmd.assert().success().stdout(predicate::str::contains("2")); // 2 files
@@ -440,7 +550,7 @@ fn test_nested_directories() {
 
 #[test]
 fn test_git_history_with_until() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--since")
@@ -453,7 +563,7 @@ fn test_git_history_with_until() {
 
 #[test]
 fn test_git_history_with_last_days() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".").arg("--history").arg("--last").arg("30");
 
     let output = cmd.assert().success();
@@ -462,7 +572,7 @@ fn test_git_history_with_last_days() {
 
 #[test]
 fn test_git_history_verbose_shows_progress() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--last")
@@ -476,7 +586,7 @@ fn test_git_history_verbose_shows_progress() {
 
 #[test]
 fn test_git_history_until_without_since() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--until")
@@ -487,7 +597,7 @@ fn test_git_history_until_without_since() {
 
 #[test]
 fn test_git_history_date_range_json() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--since")
@@ -503,7 +613,7 @@ fn test_git_history_date_range_json() {
 
 #[test]
 fn test_git_history_last_with_csv() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--last")
@@ -517,7 +627,7 @@ fn test_git_history_last_with_csv() {
 
 #[test]
 fn test_git_history_last_conflicts_with_since() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--last")
@@ -531,7 +641,7 @@ fn test_git_history_last_conflicts_with_since() {
 
 #[test]
 fn test_git_history_last_conflicts_with_until() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--last")
@@ -545,7 +655,7 @@ fn test_git_history_last_conflicts_with_until() {
 
 #[test]
 fn test_git_history_invalid_date_format() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--since")
@@ -557,7 +667,7 @@ fn test_git_history_invalid_date_format() {
 
 #[test]
 fn test_git_history_since_rfc3339_format() {
-    let mut cmd = Command::cargo_bin("sniffy").unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
     cmd.arg(".")
         .arg("--history")
         .arg("--since")
@@ -565,3 +675,264 @@ fn test_git_history_since_rfc3339_format() {
 
     cmd.assert().success();
 }
+
+#[test]
+fn test_at_revision() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--at")
+        .arg("HEAD")
+        .arg("--format")
+        .arg("json");
+
+    let output = cmd.assert().success();
+    output.stdout(predicate::str::contains("\"Rust\""));
+}
+
+#[test]
+fn test_at_invalid_revision() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--at").arg("no-such-revision");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("no-such-revision"));
+}
+
+#[test]
+fn test_at_conflicts_with_history() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--history").arg("--at").arg("HEAD");
+
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_timeline_by_month() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--timeline").arg("--by-month");
+
+    let output = cmd.assert().success();
+    output.stdout(predicate::str::contains("Codebase Timeline"));
+}
+
+#[test]
+fn test_timeline_csv_format() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--timeline")
+        .arg("--by-week")
+        .arg("--format")
+        .arg("csv");
+
+    let output = cmd.assert().success();
+    output.stdout(predicate::str::contains(
+        "date,commit,language,files,blank,comment,doc,code,total",
+    ));
+}
+
+#[test]
+fn test_git_history_by_quarter_with_limit() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--history")
+        .arg("--by-quarter")
+        .arg("--limit")
+        .arg("4");
+
+    let output = cmd.assert().success();
+    output.stdout(predicate::str::contains("Quarterly Statistics"));
+}
+
+#[test]
+fn test_git_history_multiple_periods_rejected() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--history")
+        .arg("--by-month")
+        .arg("--by-year");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("Cannot use more than one"));
+}
+
+#[test]
+fn test_git_history_by_language_csv() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--history")
+        .arg("--by-language")
+        .arg("--format")
+        .arg("csv");
+
+    let output = cmd.assert().success();
+    output.stdout(predicate::str::contains(
+        "date,language,additions_code,deletions_code,additions_doc,deletions_doc,net_change",
+    ));
+}
+
+#[test]
+fn test_git_history_hotspots() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--history").arg("--hotspots").arg("5");
+
+    let output = cmd.assert().success();
+    output
+        .stdout(predicate::str::contains("Git History Hotspots"))
+        .stdout(predicate::str::contains("Churn"));
+}
+
+#[test]
+fn test_git_history_weighted_hotspots_csv() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--history")
+        .arg("--hotspots")
+        .arg("5")
+        .arg("--weight-by-size")
+        .arg("--format")
+        .arg("csv");
+
+    let output = cmd.assert().success();
+    output.stdout(predicate::str::contains(
+        "file,commits,authors,additions_code,deletions_code,additions_doc,deletions_doc,churn,current_code,score",
+    ));
+}
+
+#[test]
+fn test_hotspots_requires_history() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--hotspots").arg("5");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("require --history"));
+}
+
+#[test]
+fn test_hotspots_conflicts_with_by_language() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".")
+        .arg("--history")
+        .arg("--hotspots")
+        .arg("5")
+        .arg("--by-language");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_co_author_credit_requires_history() {
+    // Even the default mode, when given explicitly
+    for mode in ["split", "full"] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+        cmd.arg(".").arg("--co-author-credit").arg(mode);
+
+        let output = cmd.assert().failure();
+        output.stderr(predicate::str::contains("require --history"));
+    }
+}
+
+#[test]
+fn test_merges_requires_history() {
+    // Even the default policy, when given explicitly
+    for policy in ["first-parent", "skip"] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+        cmd.arg(".").arg("--merges").arg(policy);
+
+        let output = cmd.assert().failure();
+        output.stderr(predicate::str::contains("require --history"));
+    }
+}
+
+#[test]
+fn test_rename_threshold_requires_history() {
+    // Even the default threshold, when given explicitly
+    for threshold in ["80", "50"] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+        cmd.arg(".").arg("--rename-threshold").arg(threshold);
+
+        let output = cmd.assert().failure();
+        output.stderr(predicate::str::contains("require --history"));
+    }
+}
+
+#[test]
+fn test_no_renames_requires_history() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--no-renames");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("require --history"));
+}
+
+#[test]
+fn test_ownership_csv() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg("src").arg("--ownership").arg("--format").arg("csv");
+
+    let output = cmd.assert().success();
+    output
+        .stdout(predicate::str::contains("scope,path,author,code,percent"))
+        .stdout(predicate::str::contains("directory,src,"));
+}
+
+#[test]
+fn test_ownership_bus_factor() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg("src").arg("--ownership").arg("--no-color");
+
+    let output = cmd.assert().success();
+    output
+        .stdout(predicate::str::contains("Code Ownership"))
+        .stdout(predicate::str::contains("Bus Factor:"));
+}
+
+#[test]
+fn test_ownership_conflicts_with_history() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--ownership").arg("--history");
+
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_git_history_invalid_author_pattern() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--history").arg("--author").arg("(alice");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("Invalid author pattern '(alice'"));
+}
+
+#[test]
+fn test_date_requires_history() {
+    // Even the default date source, when given explicitly
+    for date in ["author", "committer"] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+        cmd.arg(".").arg("--date").arg(date);
+
+        let output = cmd.assert().failure();
+        output.stderr(predicate::str::contains("require --history"));
+    }
+}
+
+#[test]
+fn test_timezone_requires_history() {
+    // Even the default time zone, when given explicitly
+    for timezone in ["utc", "commit"] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+        cmd.arg(".").arg("--timezone").arg(timezone);
+
+        let output = cmd.assert().failure();
+        output.stderr(predicate::str::contains("require --history"));
+    }
+}
+
+#[test]
+fn test_git_history_invalid_timezone() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("sniffy"));
+    cmd.arg(".").arg("--history").arg("--timezone").arg("Mars");
+
+    let output = cmd.assert().failure();
+    output.stderr(predicate::str::contains("Invalid time zone"));
+}